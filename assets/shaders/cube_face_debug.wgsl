@@ -0,0 +1,57 @@
+#import bevy_pbr::mesh_view_bindings
+#import bevy_pbr::mesh_bindings
+
+// NOTE: Bindings must come before functions that use them!
+#import bevy_pbr::mesh_functions
+
+// the attributes of `CubeSphere::cube_attributes`, at the locations given by
+// the `specialize` of the material
+struct Vertex {
+    @location(0) position: vec3<f32>,
+    // `ATTRIBUTE_CUBE_FACE`, the index of the face in `CubeFace::ALL`
+    @location(1) cube_face: u32,
+    // `ATTRIBUTE_CUBE_POINT`, the point on the cube, range of `[-1, 1]`
+    @location(2) cube_point: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) @interpolate(flat) cube_face: u32,
+    @location(1) cube_point: vec3<f32>,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = mesh_position_local_to_clip(mesh.model, vec4<f32>(vertex.position, 1.0));
+    out.cube_face = vertex.cube_face;
+    out.cube_point = vertex.cube_point;
+    return out;
+}
+
+struct FragmentInput {
+    @location(0) @interpolate(flat) cube_face: u32,
+    @location(1) cube_point: vec3<f32>,
+};
+
+@fragment
+fn fragment(in: FragmentInput) -> @location(0) vec4<f32> {
+    var colors = array<vec3<f32>, 6>(
+        vec3<f32>(0.9, 0.3, 0.3),
+        vec3<f32>(0.3, 0.9, 0.3),
+        vec3<f32>(0.3, 0.3, 0.9),
+        vec3<f32>(0.9, 0.9, 0.3),
+        vec3<f32>(0.9, 0.3, 0.9),
+        vec3<f32>(0.3, 0.9, 0.9),
+    );
+    let color = colors[in.cube_face];
+
+    // straight grid lines on the cube, every 1/4 of a face, the coordinate
+    // constant across the face has no width and draws no line
+    let cell = fract(in.cube_point * 2.0);
+    let width = fwidth(in.cube_point * 2.0);
+    let line = (step(cell, width) + step(1.0 - width, cell)) * step(vec3<f32>(1e-6), width);
+    let on_line = clamp(line.x + line.y + line.z, 0.0, 1.0);
+
+    return vec4<f32>(mix(color, vec3<f32>(0.1), on_line), 1.0);
+}
@@ -0,0 +1,55 @@
+#import bevy_pbr::mesh_view_bindings
+#import bevy_pbr::mesh_bindings
+
+// NOTE: Bindings must come before functions that use them!
+#import bevy_pbr::mesh_functions
+
+// a layer per face, in the order of `CubeFace::ALL`
+@group(1) @binding(0)
+var faces: texture_2d_array<f32>;
+@group(1) @binding(1)
+var faces_sampler: sampler;
+
+// the attributes of a mesh with the `FaceLayers` uv layout, at the locations
+// given by the `specialize` of the material
+struct Vertex {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    // the uv within the face, range of `[0, 1]`
+    @location(2) uv: vec2<f32>,
+    // `ATTRIBUTE_CUBE_FACE`, the index of the face in `CubeFace::ALL`
+    @location(3) cube_face: u32,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_normal: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) @interpolate(flat) cube_face: u32,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = mesh_position_local_to_clip(mesh.model, vec4<f32>(vertex.position, 1.0));
+    out.world_normal = mesh_normal_local_to_world(vertex.normal);
+    out.uv = vertex.uv;
+    out.cube_face = vertex.cube_face;
+    return out;
+}
+
+struct FragmentInput {
+    @location(0) world_normal: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) @interpolate(flat) cube_face: u32,
+};
+
+@fragment
+fn fragment(in: FragmentInput) -> @location(0) vec4<f32> {
+    let color = textureSample(faces, faces_sampler, in.uv, i32(in.cube_face));
+
+    // a fixed light, enough to see the shape of the sphere
+    let light = normalize(vec3<f32>(0.5, 1.0, 0.5));
+    let diffuse = max(dot(normalize(in.world_normal), light), 0.0);
+    return vec4<f32>(color.rgb * (0.3 + 0.7 * diffuse), color.a);
+}
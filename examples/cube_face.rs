@@ -0,0 +1,112 @@
+use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey},
+    prelude::*,
+    reflect::TypeUuid,
+    render::{mesh::MeshVertexBufferLayout, render_resource::*},
+};
+
+use bevy_cube_sphere::{CubeSphere, ATTRIBUTE_CUBE_FACE, ATTRIBUTE_CUBE_POINT};
+
+#[derive(Component)]
+struct Movable;
+
+#[derive(AsBindGroup, TypeUuid, Debug, Clone)]
+#[uuid = "6c0f4a4e-3d1b-4b9e-9a57-2f1e5b7d8c21"]
+struct CubeFaceDebugMaterial {}
+
+impl Material for CubeFaceDebugMaterial {
+    fn vertex_shader() -> ShaderRef {
+        "shaders/cube_face_debug.wgsl".into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        "shaders/cube_face_debug.wgsl".into()
+    }
+
+    /// read the cube attributes at the locations expected by the shader
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            ATTRIBUTE_CUBE_FACE.at_shader_location(1),
+            ATTRIBUTE_CUBE_POINT.at_shader_location(2),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        Ok(())
+    }
+}
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(MaterialPlugin::<CubeFaceDebugMaterial>::default())
+        .add_startup_system(setup)
+        .add_system(object_rotate)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<CubeFaceDebugMaterial>>,
+) {
+    let mesh: Mesh = CubeSphere {
+        resolution: 32,
+        cube_attributes: true,
+        ..default()
+    }
+    .into();
+
+    commands.spawn(MaterialMeshBundle::<CubeFaceDebugMaterial> {
+        mesh: meshes.add(mesh),
+        material: materials.add(CubeFaceDebugMaterial {}),
+        ..default()
+    });
+
+    commands.spawn(PointLightBundle {
+        point_light: PointLight {
+            intensity: 1500.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..default()
+    });
+
+    let camera_origin = commands.spawn((TransformBundle::default(), Movable)).id();
+
+    let camera = commands
+        .spawn(Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        })
+        .id();
+
+    commands.entity(camera_origin).add_child(camera);
+}
+
+fn object_rotate(
+    input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut query: Query<&mut Transform, With<Movable>>,
+) {
+    for mut transform in &mut query {
+        if input.pressed(KeyCode::Up) {
+            transform.rotate_x(time.delta_seconds());
+        }
+        if input.pressed(KeyCode::Down) {
+            transform.rotate_x(-time.delta_seconds());
+        }
+        if input.pressed(KeyCode::Left) {
+            transform.rotate_y(time.delta_seconds());
+        }
+        if input.pressed(KeyCode::Right) {
+            transform.rotate_y(-time.delta_seconds());
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use bevy::{
+    pbr::wireframe::{Wireframe, WireframePlugin},
+    prelude::*,
+    render::{render_resource::WgpuFeatures, settings::WgpuSettings},
+};
+
+use bevy_cube_sphere::{CellId, CubeSphere, LodQuadtree, LodSettings, Viewpoint};
+
+const PLANET_RADIUS: f32 = 100.0;
+
+#[derive(Component)]
+struct Movable;
+
+#[derive(Resource)]
+struct Planet {
+    quadtree: LodQuadtree,
+    material: Handle<StandardMaterial>,
+    chunks: HashMap<CellId, Entity>,
+}
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .insert_resource(WgpuSettings {
+            features: WgpuFeatures::POLYGON_MODE_LINE,
+            ..default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WireframePlugin)
+        .add_startup_system(setup)
+        .add_system(camera_move)
+        .add_system(update_chunks)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut materials: ResMut<Assets<StandardMaterial>>) {
+    let sphere = CubeSphere {
+        radius: PLANET_RADIUS,
+        ..default()
+    };
+    let quadtree = LodQuadtree::new(sphere, LodSettings::default()).unwrap();
+
+    commands.insert_resource(Planet {
+        quadtree,
+        material: materials.add(Color::ORANGE.into()),
+        chunks: HashMap::new(),
+    });
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 0.0, 3.0 * PLANET_RADIUS)
+                .looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        Movable,
+    ));
+}
+
+/// `Up` and `Down` move the camera towards and away from the surface,
+/// `Left` and `Right` move it around the planet
+fn camera_move(
+    input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut query: Query<&mut Transform, With<Movable>>,
+) {
+    for mut transform in &mut query {
+        let altitude = transform.translation.length() - PLANET_RADIUS;
+        let step = altitude * time.delta_seconds();
+
+        if input.pressed(KeyCode::Up) {
+            transform.translation = transform.translation.normalize()
+                * (PLANET_RADIUS + altitude - step).max(PLANET_RADIUS + 0.01);
+        }
+        if input.pressed(KeyCode::Down) {
+            transform.translation =
+                transform.translation.normalize() * (PLANET_RADIUS + altitude + step);
+        }
+        if input.pressed(KeyCode::Left) {
+            transform.rotate_around(Vec3::ZERO, Quat::from_rotation_y(-time.delta_seconds()));
+        }
+        if input.pressed(KeyCode::Right) {
+            transform.rotate_around(Vec3::ZERO, Quat::from_rotation_y(time.delta_seconds()));
+        }
+    }
+}
+
+/// spawn the patches entering the selection, despawn the ones leaving it and
+/// regenerate the ones whose neighbors changed level
+fn update_chunks(
+    mut commands: Commands,
+    mut planet: ResMut<Planet>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    windows: Res<Windows>,
+    camera: Query<(&Transform, &Projection), With<Camera>>,
+) {
+    let (transform, projection) = camera.single();
+    let fov = match projection {
+        Projection::Perspective(perspective) => perspective.fov,
+        Projection::Orthographic(_) => return,
+    };
+    let height = windows.primary().physical_height() as f32;
+    let viewpoint = Viewpoint::perspective(transform.translation.to_array(), fov, height);
+
+    let planet = &mut *planet;
+    let update = planet.quadtree.update(&viewpoint);
+
+    for cell in update.removed {
+        if let Some(entity) = planet.chunks.remove(&cell) {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for cell in update.restitched {
+        if let Some(entity) = planet.chunks.get(&cell) {
+            let mesh = meshes.add(planet.quadtree.chunk_mesh(cell).unwrap());
+            commands.entity(*entity).insert(mesh);
+        }
+    }
+
+    for cell in update.added {
+        let entity = commands
+            .spawn((
+                PbrBundle {
+                    mesh: meshes.add(planet.quadtree.chunk_mesh(cell).unwrap()),
+                    material: planet.material.clone(),
+                    ..default()
+                },
+                Wireframe,
+            ))
+            .id();
+        planet.chunks.insert(cell, entity);
+    }
+}
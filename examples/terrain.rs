@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use bevy_cube_sphere::noise::Fractal;
+use bevy_cube_sphere::{CubeSphere, NoiseSettings};
+
+#[derive(Component)]
+struct Movable;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(object_rotate)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let terrain = NoiseSettings {
+        seed: 42,
+        fractal: Fractal::Ridged,
+        amplitude: 0.08,
+        warp: 0.2,
+        ..default()
+    };
+
+    let mesh: Mesh = CubeSphere {
+        resolution: 128,
+        elevation: Some(Arc::new(terrain)),
+        ..default()
+    }
+    .into();
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(mesh),
+            material: materials.add(Color::ORANGE.into()),
+            ..default()
+        },
+        Movable,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 0.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
+
+fn object_rotate(
+    input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut query: Query<&mut Transform, With<Movable>>,
+) {
+    for mut transform in &mut query {
+        if input.pressed(KeyCode::Up) {
+            transform.rotate_x(time.delta_seconds());
+        }
+        if input.pressed(KeyCode::Down) {
+            transform.rotate_x(-time.delta_seconds());
+        }
+        if input.pressed(KeyCode::Left) {
+            transform.rotate_y(time.delta_seconds());
+        }
+        if input.pressed(KeyCode::Right) {
+            transform.rotate_y(-time.delta_seconds());
+        }
+    }
+}
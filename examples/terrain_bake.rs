@@ -0,0 +1,40 @@
+use bevy_cube_sphere::noise::Fractal;
+use bevy_cube_sphere::{CubeSphere, NoiseSettings};
+use image::LumaA;
+
+const WIDTH: u32 = 1024;
+const HEIGHT: u32 = 1024;
+
+fn main() {
+    let terrain = NoiseSettings {
+        seed: 42,
+        fractal: Fractal::Ridged,
+        amplitude: 1.,
+        warp: 0.2,
+        ..Default::default()
+    };
+
+    let sphere = CubeSphere::default();
+    let mut image = image::GrayAlphaImage::new(WIDTH, HEIGHT);
+
+    for ix in 0..WIDTH {
+        for iy in 0..HEIGHT {
+            let x = ix as f32 / WIDTH as f32;
+            let y = iy as f32 / HEIGHT as f32;
+
+            // the same noise as the elevation of the mesh, so the texture
+            // lines up with the displaced vertices
+            let pixel = match sphere.uv_to_point(&[x, y]) {
+                Some(pt) => {
+                    let height = (terrain.sample(&pt) + 1.) / 2.;
+                    LumaA([(height * 255.) as u8, 255])
+                }
+                None => LumaA([0, 0]),
+            };
+            *image.get_pixel_mut(ix, iy) = pixel;
+        }
+    }
+
+    // write it out to a file
+    image.save("height.png").unwrap();
+}
@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey},
+    prelude::*,
+    reflect::TypeUuid,
+    render::{mesh::MeshVertexBufferLayout, render_resource::*},
+};
+
+use bevy_cube_sphere::uv::FaceLayers;
+use bevy_cube_sphere::{CubeFace, CubeSphere, ATTRIBUTE_CUBE_FACE};
+
+/// the number of texels along each side of a layer
+const LAYER_SIZE: u32 = 256;
+
+#[derive(Component)]
+struct Movable;
+
+#[derive(AsBindGroup, TypeUuid, Debug, Clone)]
+#[uuid = "1f9b7c52-8e4d-4a36-b0c1-7d2e6a9f3b48"]
+struct FaceArrayMaterial {
+    /// a six layer array, a layer per face in the order of `CubeFace::ALL`
+    #[texture(0, dimension = "2d_array")]
+    #[sampler(1)]
+    faces: Handle<Image>,
+}
+
+impl Material for FaceArrayMaterial {
+    fn vertex_shader() -> ShaderRef {
+        "shaders/face_array.wgsl".into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        "shaders/face_array.wgsl".into()
+    }
+
+    /// read the face attribute at the location expected by the shader
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_NORMAL.at_shader_location(1),
+            Mesh::ATTRIBUTE_UV_0.at_shader_location(2),
+            ATTRIBUTE_CUBE_FACE.at_shader_location(3),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        Ok(())
+    }
+}
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(MaterialPlugin::<FaceArrayMaterial>::default())
+        .add_startup_system(setup)
+        .add_system(object_rotate)
+        .run();
+}
+
+/// a checkerboard per face, tinted by face, with the texels of the layer
+/// computed from the points of the sphere they show
+fn face_layers(sphere: &CubeSphere) -> Image {
+    let tints = [
+        [230, 80, 80],
+        [80, 230, 80],
+        [80, 80, 230],
+        [230, 230, 80],
+        [230, 80, 230],
+        [80, 230, 230],
+    ];
+
+    let mut data = Vec::with_capacity((6 * LAYER_SIZE * LAYER_SIZE * 4) as usize);
+    for (face, tint) in CubeFace::ALL.into_iter().zip(tints) {
+        for iy in 0..LAYER_SIZE {
+            for ix in 0..LAYER_SIZE {
+                let uv = [
+                    (ix as f32 + 0.5) / LAYER_SIZE as f32,
+                    (iy as f32 + 0.5) / LAYER_SIZE as f32,
+                ];
+                let pt = sphere.face_uv_to_point(face, &uv).unwrap();
+
+                // a checker of cells of space, continuous across the borders of the faces
+                let checker = pt.iter().map(|c| (c * 4.).floor() as i32).sum::<i32>() & 1;
+                let shade = if checker == 0 { 1. } else { 0.6 };
+                data.extend(tint.map(|c| (c as f32 * shade) as u8));
+                data.push(255);
+            }
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: LAYER_SIZE,
+            height: LAYER_SIZE,
+            depth_or_array_layers: 6,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<FaceArrayMaterial>>,
+) {
+    let sphere = CubeSphere {
+        resolution: 32,
+        uv_layout: Arc::new(FaceLayers),
+        ..default()
+    };
+    let faces = images.add(face_layers(&sphere));
+
+    commands.spawn(MaterialMeshBundle::<FaceArrayMaterial> {
+        mesh: meshes.add(sphere.into()),
+        material: materials.add(FaceArrayMaterial { faces }),
+        ..default()
+    });
+
+    let camera_origin = commands.spawn((TransformBundle::default(), Movable)).id();
+
+    let camera = commands
+        .spawn(Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        })
+        .id();
+
+    commands.entity(camera_origin).add_child(camera);
+}
+
+fn object_rotate(
+    input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut query: Query<&mut Transform, With<Movable>>,
+) {
+    for mut transform in &mut query {
+        if input.pressed(KeyCode::Up) {
+            transform.rotate_x(time.delta_seconds());
+        }
+        if input.pressed(KeyCode::Down) {
+            transform.rotate_x(-time.delta_seconds());
+        }
+        if input.pressed(KeyCode::Left) {
+            transform.rotate_y(time.delta_seconds());
+        }
+        if input.pressed(KeyCode::Right) {
+            transform.rotate_y(-time.delta_seconds());
+        }
+    }
+}
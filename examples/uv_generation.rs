@@ -5,14 +5,15 @@ const WIDTH: u32 = 1024;
 const HEIGHT: u32 = 1024;
 
 fn main() {
+    let sphere = CubeSphere::default();
     let mut image = RgbaImage::new(WIDTH, HEIGHT);
 
     for ix in 0..WIDTH {
         for iy in 0..HEIGHT {
             let x = ix as f32 / WIDTH as f32;
-            let y = 1. - iy as f32 / WIDTH as f32;
+            let y = iy as f32 / HEIGHT as f32;
 
-            let face = CubeSphere::uv_to_point(&[x, y]);
+            let face = sphere.uv_to_point(&[x, y]);
 
             match face {
                 Some(_) => *image.get_pixel_mut(ix, iy) = Rgba([0, 0, 255, 255]),
@@ -1,11 +1,7 @@
-use bevy::{
-    pbr::wireframe::{Wireframe, WireframeConfig, WireframePlugin},
-    prelude::*,
-    render::{render_resource::WgpuFeatures, settings::WgpuSettings},
-};
+use bevy::prelude::*;
 
 use bevy::render::render_resource::Face;
-use bevy_cube_sphere::CubeSphere;
+use bevy_cube_sphere::{CubeSphere, GridLines};
 
 #[derive(Component)]
 struct Movable;
@@ -13,12 +9,7 @@ struct Movable;
 fn main() {
     App::new()
         .insert_resource(Msaa { samples: 4 })
-        .insert_resource(WgpuSettings {
-            features: WgpuFeatures::POLYGON_MODE_LINE,
-            ..default()
-        })
         .add_plugins(DefaultPlugins)
-        .add_plugin(WireframePlugin)
         .add_startup_system(setup)
         .add_system(object_rotate)
         .run();
@@ -26,13 +17,13 @@ fn main() {
 
 fn setup(
     mut commands: Commands,
-    mut wireframe_config: ResMut<WireframeConfig>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    wireframe_config.global = false;
-
-    let mesh: Mesh = CubeSphere::default().into();
+    let sphere = CubeSphere::default();
+    let mesh = sphere.try_mesh().unwrap();
+    // a line list drawn over the triangles, available on every backend
+    let lines = sphere.try_wireframe_mesh(GridLines::All).unwrap();
 
     commands.spawn((
         PbrBundle {
@@ -44,7 +35,21 @@ fn setup(
             }),
             ..default()
         },
-        Wireframe,
+        Movable,
+    ));
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(lines),
+            material: materials.add(StandardMaterial {
+                base_color: Color::BLACK,
+                unlit: true,
+                ..default()
+            }),
+            // slightly above the triangles, so the lines win the depth test
+            transform: Transform::from_scale(Vec3::splat(1.001)),
+            ..default()
+        },
         Movable,
     ));
 
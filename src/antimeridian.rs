@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use crate::clip::{cut_direction, insert_cut_vertex};
+use crate::elevation::orthogonalize;
+use crate::{CubeFace, CubeSphere, MeshData};
+
+/// how close to the axis of the poles a vertex lies on a pole, where `u`
+/// means nothing
+const POLE_TOLERANCE: f32 = 1e-6;
+
+/// cut the triangles of the sphere along the antimeridian, the half plane
+/// `x = 0, z < 0`, and give the vertices on it and on the poles the `u` of
+/// each of their triangles, for the uv layouts wrapping around along it
+/// ## Arguments
+/// - `sphere` - the sphere being generated
+/// - `step` - the angle between the samples of the displaced normal
+/// - `data` - the triangles, the cut vertices and the copies are appended to them
+/// - `directions` - the unit sphere point of every vertex, extended with the
+///   appended vertices
+/// - `faces` - the face of every vertex, extended with the appended vertices
+pub(crate) fn wrap(
+    sphere: &CubeSphere,
+    step: f32,
+    data: &mut MeshData,
+    directions: &mut Vec<[f32; 3]>,
+    faces: &mut Vec<CubeFace>,
+) {
+    cut(sphere, step, data, directions, faces);
+
+    let is_pole = |d: &[f32; 3]| d[0].hypot(d[2]) < POLE_TOLERANCE;
+
+    // the copy with `u - 1` of a vertex of the east side, shared by the
+    // triangles of the west side
+    let mut west: HashMap<u32, u32> = HashMap::new();
+    let triangles = std::mem::take(&mut data.indices);
+
+    for triangle in triangles.chunks(3) {
+        let mut corners = [triangle[0], triangle[1], triangle[2]];
+        let poles = corners.map(|v| is_pole(&directions[v as usize]));
+
+        let us = (0..3)
+            .filter(|k| !poles[*k])
+            .map(|k| data.uvs[corners[k] as usize][0]);
+        let (min, max) = us.fold((f32::MAX, f32::MIN), |(min, max), u| {
+            (min.min(u), max.max(u))
+        });
+        if max - min > 0.5 {
+            for k in (0..3).filter(|k| !poles[*k]) {
+                let v = corners[k];
+                if data.uvs[v as usize][0] > 0.5 {
+                    corners[k] = *west.entry(v).or_insert_with(|| {
+                        let copy = copy_vertex(v, data, directions, faces);
+                        data.uvs[copy as usize][0] -= 1.;
+                        copy
+                    });
+                }
+            }
+        }
+
+        // a pole takes the `u` of the middle of the other vertices, and the
+        // direction of their tangents
+        for k in (0..3).filter(|k| poles[*k]) {
+            let others: Vec<u32> = (0..3).filter(|o| !poles[*o]).map(|o| corners[o]).collect();
+            if others.is_empty() {
+                continue;
+            }
+
+            let copy = copy_vertex(corners[k], data, directions, faces);
+            let mut u = 0.;
+            let mut sum = [0.; 3];
+            for other in &others {
+                let tangent = data.tangents[*other as usize];
+                u += data.uvs[*other as usize][0] / others.len() as f32;
+                sum = [0, 1, 2].map(|c| sum[c] + tangent[c]);
+            }
+            let w = data.tangents[others[0] as usize][3];
+            let tangent = [sum[0], sum[1], sum[2], w];
+            data.uvs[copy as usize][0] = u;
+            data.tangents[copy as usize] = orthogonalize(&tangent, &data.normals[copy as usize]);
+            corners[k] = copy;
+        }
+
+        data.indices.extend(corners);
+    }
+}
+
+/// cut the triangles crossing the antimeridian into a part on its east side
+/// and a part on its west side
+fn cut(
+    sphere: &CubeSphere,
+    step: f32,
+    data: &mut MeshData,
+    directions: &mut Vec<[f32; 3]>,
+    faces: &mut Vec<CubeFace>,
+) {
+    let normal = [1., 0., 0.];
+    let side: Vec<f64> = directions.iter().map(|d| f64::from(d[0])).collect();
+    let crosses =
+        |a: usize, b: usize| (side[a] > 0. && side[b] < 0.) || (side[a] < 0. && side[b] > 0.);
+
+    // the vertex of every cut edge, shared by the two triangles of the edge
+    let mut cuts: HashMap<(u32, u32), u32> = HashMap::new();
+    let triangles = std::mem::take(&mut data.indices);
+    let mut polygon = Vec::with_capacity(4);
+
+    for triangle in triangles.chunks(3) {
+        // the plane `x = 0` goes through the center, so the chord crosses it
+        // in the direction of the arc. The triangles crossing the prime
+        // meridian only are kept whole
+        let on_antimeridian = (0..3).any(|k| {
+            let (a, b) = (triangle[k] as usize, triangle[(k + 1) % 3] as usize);
+            let t = side[a] / (side[a] - side[b]);
+            let z = f64::from(directions[a][2]) * (1. - t) + f64::from(directions[b][2]) * t;
+            crosses(a, b) && z <= 0.
+        });
+        if !on_antimeridian {
+            data.indices.extend_from_slice(triangle);
+            continue;
+        }
+
+        for sign in [1., -1.] {
+            polygon.clear();
+            for k in 0..3 {
+                let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+                if sign * side[a as usize] >= 0. {
+                    polygon.push(a);
+                }
+                if crosses(a as usize, b as usize) {
+                    let key = (a.min(b), a.max(b));
+                    let cut = *cuts.entry(key).or_insert_with(|| {
+                        let mut direction = cut_direction(
+                            &directions[a as usize],
+                            &directions[b as usize],
+                            &normal,
+                            0.,
+                        );
+                        // exactly on the plane, so that its `u` is the one of
+                        // the east side, the length is left as it rounds to `1`
+                        direction[0] = 0.;
+                        let face = faces[a as usize];
+                        insert_cut_vertex(sphere, step, direction, face, data, directions, faces)
+                    });
+                    polygon.push(cut);
+                }
+            }
+
+            // a fan over the part, a triangle or a quad, in the winding of
+            // the original triangle
+            for k in 1..polygon.len().saturating_sub(1) {
+                let (a, b, c) = (polygon[0], polygon[k], polygon[k + 1]);
+                if a != b && b != c && c != a {
+                    data.indices.extend([a, b, c]);
+                }
+            }
+        }
+    }
+}
+
+/// append a copy of a vertex
+/// ## Return
+/// - the index of the copy
+fn copy_vertex(
+    v: u32,
+    data: &mut MeshData,
+    directions: &mut Vec<[f32; 3]>,
+    faces: &mut Vec<CubeFace>,
+) -> u32 {
+    let v = v as usize;
+    data.points.push(data.points[v]);
+    data.normals.push(data.normals[v]);
+    data.tangents.push(data.tangents[v]);
+    data.uvs.push(data.uvs[v]);
+    for column in &mut data.custom {
+        column.values.push(column.values[v]);
+    }
+    directions.push(directions[v]);
+    faces.push(faces[v]);
+    directions.len() as u32 - 1
+}
@@ -0,0 +1,210 @@
+use bevy::render::mesh::{MeshVertexAttribute, VertexAttributeValues};
+use bevy::render::render_resource::VertexFormat;
+
+use crate::CubeFace;
+
+/// the index of the face of a vertex in `CubeFace::ALL`, written with
+/// [`crate::CubeSphere::cube_attributes`] or a uv layout that needs it such
+/// as [`crate::uv::FaceLayers`], a welded mesh keeps a copy of a border
+/// vertex per face then
+///
+/// read it in WGSL as a flat `u32`, e.g. `@location(1) cube_face: u32`
+pub const ATTRIBUTE_CUBE_FACE: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_CubeFace", 3_125_770_301, VertexFormat::Uint32);
+
+/// the location of a vertex on the cube before the projection, range of
+/// `[-1, 1]`, written with [`crate::CubeSphere::cube_attributes`]
+///
+/// read it in WGSL as a `vec3<f32>`, e.g. `@location(2) cube_point: vec3<f32>`
+pub const ATTRIBUTE_CUBE_POINT: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_CubePoint", 3_125_770_302, VertexFormat::Float32x3);
+
+/// extra per-vertex data computed while the sphere is generated, e.g. a
+/// biome id in `Mesh::ATTRIBUTE_COLOR` or in a custom attribute of a shader
+///
+/// a [`crate::CubeSphere::welded`] mesh only shares the copies of a border
+/// vertex whose values are equal, values depending on the face keep a copy
+/// per face
+pub trait AttributeHook: Send + Sync {
+    /// the attributes written for every vertex, in the order of the values of
+    /// [`AttributeHook::compute`], they replace the generated ones of the same id
+    fn attributes(&self) -> Vec<MeshVertexAttribute>;
+
+    /// compute the values of the attributes of a vertex
+    /// ## Arguments
+    /// - `vertex` - where the vertex is on the cube and on the sphere
+    /// - `values` - one value per attribute, set to zero in the format of the
+    ///   attribute, to be overwritten with values of the same format
+    fn compute(&self, vertex: &VertexInfo, values: &mut [AttributeValue]);
+}
+
+/// a vertex being generated, given to [`AttributeHook::compute`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VertexInfo {
+    /// the location of the vertex on the cube, range of `[-1, 1]`
+    pub cube_point: [f32; 3],
+    /// the location of the vertex on the unit sphere, before the radius,
+    /// the radii and the elevation are applied
+    pub sphere_point: [f32; 3],
+    /// the face the vertex belongs to
+    pub face: CubeFace,
+    /// the indices of the vertex along the outer and inner loop of its grid,
+    /// the grid of the face for a sphere and of the cell for a patch, `None`
+    /// for the vertices added along the boundary of a clip
+    pub grid: Option<[u32; 2]>,
+}
+
+/// the value of an attribute for a single vertex
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttributeValue {
+    /// a value of `VertexFormat::Float32`
+    Float32(f32),
+    /// a value of `VertexFormat::Sint32`
+    Sint32(i32),
+    /// a value of `VertexFormat::Uint32`
+    Uint32(u32),
+    /// a value of `VertexFormat::Float32x2`
+    Float32x2([f32; 2]),
+    /// a value of `VertexFormat::Sint32x2`
+    Sint32x2([i32; 2]),
+    /// a value of `VertexFormat::Uint32x2`
+    Uint32x2([u32; 2]),
+    /// a value of `VertexFormat::Float32x3`
+    Float32x3([f32; 3]),
+    /// a value of `VertexFormat::Sint32x3`
+    Sint32x3([i32; 3]),
+    /// a value of `VertexFormat::Uint32x3`
+    Uint32x3([u32; 3]),
+    /// a value of `VertexFormat::Float32x4`
+    Float32x4([f32; 4]),
+    /// a value of `VertexFormat::Sint32x4`
+    Sint32x4([i32; 4]),
+    /// a value of `VertexFormat::Uint32x4`
+    Uint32x4([u32; 4]),
+}
+
+impl AttributeValue {
+    /// the zero of a vertex format
+    /// ## Return
+    /// - `None` if the format is not one of the 32 bit formats
+    pub fn zero(format: VertexFormat) -> Option<Self> {
+        Some(match format {
+            VertexFormat::Float32 => AttributeValue::Float32(0.),
+            VertexFormat::Sint32 => AttributeValue::Sint32(0),
+            VertexFormat::Uint32 => AttributeValue::Uint32(0),
+            VertexFormat::Float32x2 => AttributeValue::Float32x2([0.; 2]),
+            VertexFormat::Sint32x2 => AttributeValue::Sint32x2([0; 2]),
+            VertexFormat::Uint32x2 => AttributeValue::Uint32x2([0; 2]),
+            VertexFormat::Float32x3 => AttributeValue::Float32x3([0.; 3]),
+            VertexFormat::Sint32x3 => AttributeValue::Sint32x3([0; 3]),
+            VertexFormat::Uint32x3 => AttributeValue::Uint32x3([0; 3]),
+            VertexFormat::Float32x4 => AttributeValue::Float32x4([0.; 4]),
+            VertexFormat::Sint32x4 => AttributeValue::Sint32x4([0; 4]),
+            VertexFormat::Uint32x4 => AttributeValue::Uint32x4([0; 4]),
+            _ => return None,
+        })
+    }
+
+    /// the bits of the value, padded with zeros, to compare values exactly
+    pub(crate) fn to_bits(self) -> [u32; 4] {
+        use AttributeValue as A;
+
+        fn pad<const N: usize>(bits: [u32; N]) -> [u32; 4] {
+            let mut padded = [0; 4];
+            padded[..N].copy_from_slice(&bits);
+            padded
+        }
+
+        match self {
+            A::Float32(x) => pad([x.to_bits()]),
+            A::Sint32(x) => pad([x as u32]),
+            A::Uint32(x) => pad([x]),
+            A::Float32x2(x) => pad(x.map(f32::to_bits)),
+            A::Sint32x2(x) => pad(x.map(|c| c as u32)),
+            A::Uint32x2(x) => pad(x),
+            A::Float32x3(x) => pad(x.map(f32::to_bits)),
+            A::Sint32x3(x) => pad(x.map(|c| c as u32)),
+            A::Uint32x3(x) => pad(x),
+            A::Float32x4(x) => x.map(f32::to_bits),
+            A::Sint32x4(x) => x.map(|c| c as u32),
+            A::Uint32x4(x) => x,
+        }
+    }
+}
+
+/// the values of a custom attribute for every vertex of a mesh
+#[derive(Clone)]
+pub(crate) struct AttributeColumn {
+    pub(crate) attribute: MeshVertexAttribute,
+    pub(crate) values: Vec<AttributeValue>,
+}
+
+impl AttributeColumn {
+    /// convert to the values of a bevy mesh
+    /// ## Return
+    /// - `None` if a value does not match the format of the attribute
+    pub(crate) fn into_values(self) -> Option<VertexAttributeValues> {
+        use AttributeValue as A;
+        use VertexAttributeValues as V;
+
+        fn collect<T>(
+            values: Vec<AttributeValue>,
+            f: impl Fn(AttributeValue) -> Option<T>,
+        ) -> Option<Vec<T>> {
+            values.into_iter().map(f).collect()
+        }
+
+        let values = self.values;
+        Some(match self.attribute.format {
+            VertexFormat::Float32 => V::Float32(collect(values, |v| match v {
+                A::Float32(x) => Some(x),
+                _ => None,
+            })?),
+            VertexFormat::Sint32 => V::Sint32(collect(values, |v| match v {
+                A::Sint32(x) => Some(x),
+                _ => None,
+            })?),
+            VertexFormat::Uint32 => V::Uint32(collect(values, |v| match v {
+                A::Uint32(x) => Some(x),
+                _ => None,
+            })?),
+            VertexFormat::Float32x2 => V::Float32x2(collect(values, |v| match v {
+                A::Float32x2(x) => Some(x),
+                _ => None,
+            })?),
+            VertexFormat::Sint32x2 => V::Sint32x2(collect(values, |v| match v {
+                A::Sint32x2(x) => Some(x),
+                _ => None,
+            })?),
+            VertexFormat::Uint32x2 => V::Uint32x2(collect(values, |v| match v {
+                A::Uint32x2(x) => Some(x),
+                _ => None,
+            })?),
+            VertexFormat::Float32x3 => V::Float32x3(collect(values, |v| match v {
+                A::Float32x3(x) => Some(x),
+                _ => None,
+            })?),
+            VertexFormat::Sint32x3 => V::Sint32x3(collect(values, |v| match v {
+                A::Sint32x3(x) => Some(x),
+                _ => None,
+            })?),
+            VertexFormat::Uint32x3 => V::Uint32x3(collect(values, |v| match v {
+                A::Uint32x3(x) => Some(x),
+                _ => None,
+            })?),
+            VertexFormat::Float32x4 => V::Float32x4(collect(values, |v| match v {
+                A::Float32x4(x) => Some(x),
+                _ => None,
+            })?),
+            VertexFormat::Sint32x4 => V::Sint32x4(collect(values, |v| match v {
+                A::Sint32x4(x) => Some(x),
+                _ => None,
+            })?),
+            VertexFormat::Uint32x4 => V::Uint32x4(collect(values, |v| match v {
+                A::Uint32x4(x) => Some(x),
+                _ => None,
+            })?),
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,198 @@
+use crate::face::CubeFace;
+use crate::grid::GridCoord;
+use crate::projection::CubeProjection;
+use crate::{face_coords_to_unit_cube_point, sphere_point_to_face_coords};
+
+/// a cell of a quadtree over every face of the cube, packed in a `u64`
+///
+/// the layout follows S2: the top 3 bits hold the face, followed by two bits
+/// per level giving the child taken at that level along a Morton curve, then a
+/// single set bit marking the level. Cells of the same subtree are contiguous,
+/// so sorting ids groups them by face, then by parent.
+///
+/// at level `l` a face is split into `2^l × 2^l` cells, cell `(i, j)` covers
+/// the face coordinates `a` in `[-1 + 2i / 2^l, -1 + 2(i + 1) / 2^l]`, and
+/// likewise `b` with `j`, along the axes of [`CubeFace::axes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellId(u64);
+
+const FACE_BITS: u32 = 3;
+const POS_BITS: u32 = 2 * CellId::MAX_LEVEL as u32 + 1;
+
+impl CellId {
+    /// the deepest level a cell can have
+    pub const MAX_LEVEL: u8 = 30;
+
+    /// the cell covering a whole face
+    pub fn from_face(face: CubeFace) -> Self {
+        CellId(((face.index() as u64) << POS_BITS) | (1 << (POS_BITS - 1)))
+    }
+
+    /// the cell of a face at a level
+    /// ## Arguments
+    /// - `face` - the face of the cell
+    /// - `level` - the level of the cell, at most [`CellId::MAX_LEVEL`]
+    /// - `i`, `j` - the index of the cell along the outer and inner loop of the face, below `2^level`
+    /// ## Panics
+    /// - if `level` is deeper than [`CellId::MAX_LEVEL`], or `i` or `j` is not below `2^level`
+    pub fn from_face_ij(face: CubeFace, level: u8, i: u32, j: u32) -> Self {
+        assert!(level <= CellId::MAX_LEVEL, "level {} is too deep", level);
+        assert!(
+            (i as u64) < 1 << level && (j as u64) < 1 << level,
+            "cell ({}, {}) is outside of level {}",
+            i,
+            j,
+            level
+        );
+
+        let mut pos = 0_u64;
+        for bit in (0..level).rev() {
+            let digit = (((i >> bit) & 1) << 1) | ((j >> bit) & 1);
+            pos = (pos << 2) | digit as u64;
+        }
+
+        let lsb = CellId::lsb_for_level(level);
+        CellId(((face.index() as u64) << POS_BITS) | (pos * 2 * lsb) | lsb)
+    }
+
+    /// the cell containing a point of the sphere
+    /// ## Arguments
+    /// - `dir` - the direction of the point from the center of the sphere, any length
+    /// - `level` - the level of the cell, at most [`CellId::MAX_LEVEL`]
+    /// - `projection` - the projection from the cube to the sphere
+    /// ## Panics
+    /// - if `level` is deeper than [`CellId::MAX_LEVEL`]
+    pub fn from_point(dir: &[f32; 3], level: u8, projection: &dyn CubeProjection) -> Self {
+        let (face, coords) = sphere_point_to_face_coords(dir, projection);
+        let size = (1_u64 << level) as f64;
+        let [i, j] = coords.map(|c| {
+            let st = (c as f64 + 1.) / 2.;
+            (st * size).floor().clamp(0., size - 1.) as u32
+        });
+        CellId::from_face_ij(face, level, i, j)
+    }
+
+    /// rebuild a cell from [`CellId::raw`]
+    /// ## Return
+    /// - `None` if the value is not a valid cell
+    pub fn from_raw(raw: u64) -> Option<Self> {
+        let id = CellId(raw);
+        let face_valid = (raw >> POS_BITS) < CubeFace::ALL.len() as u64;
+        let zeros = raw.trailing_zeros();
+        let level_valid = zeros & 1 == 0 && zeros <= 2 * CellId::MAX_LEVEL as u32;
+        (face_valid && level_valid).then_some(id)
+    }
+
+    /// the packed value of the cell, stable across versions
+    pub fn raw(&self) -> u64 {
+        self.0
+    }
+
+    /// the face of the cell
+    pub fn face(&self) -> CubeFace {
+        CubeFace::ALL[(self.0 >> POS_BITS) as usize]
+    }
+
+    /// the level of the cell, `0` for a whole face
+    pub fn level(&self) -> u8 {
+        CellId::MAX_LEVEL - (self.0.trailing_zeros() / 2) as u8
+    }
+
+    /// the index of the cell along the outer and inner loop of its face, below `2^level`
+    pub fn ij(&self) -> [u32; 2] {
+        let level = self.level();
+        let mut pos = (self.0 & ((1 << POS_BITS) - 1)) >> (2 * (CellId::MAX_LEVEL - level) + 1);
+        let mut ij = [0_u32; 2];
+        for bit in 0..level {
+            ij[0] |= ((pos >> 1) as u32 & 1) << bit;
+            ij[1] |= (pos as u32 & 1) << bit;
+            pos >>= 2;
+        }
+        ij
+    }
+
+    /// the cell one level up containing this one, `None` for a whole face
+    pub fn parent(&self) -> Option<CellId> {
+        match self.level() {
+            0 => None,
+            level => Some(self.parent_at(level - 1)),
+        }
+    }
+
+    /// the cell containing this one at a lower level
+    /// ## Arguments
+    /// - `level` - the level of the parent, at most the level of this cell
+    /// ## Panics
+    /// - if `level` is deeper than the level of this cell
+    pub fn parent_at(&self, level: u8) -> CellId {
+        assert!(level <= self.level(), "level {} is below the cell", level);
+        let lsb = CellId::lsb_for_level(level);
+        CellId((self.0 & !(2 * lsb - 1)) | lsb)
+    }
+
+    /// the four cells one level down, in Morton order, `None` at [`CellId::MAX_LEVEL`]
+    pub fn children(&self) -> Option<[CellId; 4]> {
+        if self.level() == CellId::MAX_LEVEL {
+            return None;
+        }
+
+        let lsb = self.lsb();
+        let child_lsb = lsb >> 2;
+        Some([0, 1, 2, 3].map(|k| CellId(self.0 - lsb + (2 * k + 1) * child_lsb)))
+    }
+
+    /// whether a cell is this one or lies inside it
+    pub fn contains(&self, other: &CellId) -> bool {
+        let lsb = self.lsb();
+        self.0 - (lsb - 1) <= other.0 && other.0 <= self.0 + (lsb - 1)
+    }
+
+    /// the cell as an element of the grid with `2^level + 1` vertices on the edge of a face
+    pub fn grid_coord(&self) -> GridCoord {
+        let [i, j] = self.ij();
+        GridCoord::new(self.face(), i, j)
+    }
+
+    /// the four cells of the same level sharing an edge with this one, crossing
+    /// to the neighboring faces at the borders
+    /// ## Return
+    /// - the neighbors, in the order of [`crate::FaceEdge::ALL`]
+    pub fn edge_neighbors(&self) -> [CellId; 4] {
+        let level = self.level();
+        let resolution = (1_usize << level) + 1;
+        self.grid_coord()
+            .cell_neighbors4(resolution)
+            .map(|c| CellId::from_face_ij(c.face, level, c.i, c.j))
+    }
+
+    /// the range of the face coordinates covered by the cell
+    /// ## Return
+    /// - the lowest and highest corner of the cell, range of `[-1, 1]`
+    pub fn face_bounds(&self) -> [[f32; 2]; 2] {
+        let size = (1_u64 << self.level()) as f64;
+        let [i, j] = self.ij().map(|c| c as f64);
+        let coord = |c: f64| (2. * c / size - 1.) as f32;
+        [[coord(i), coord(j)], [coord(i + 1.), coord(j + 1.)]]
+    }
+
+    /// the point of the sphere at the center of the cell
+    /// ## Arguments
+    /// - `projection` - the projection from the cube to the sphere
+    /// ## Return
+    /// - the location of sphere vertex, range of `[-1, 1]`
+    pub fn center(&self, projection: &dyn CubeProjection) -> [f32; 3] {
+        let [min, max] = self.face_bounds();
+        let coords = [(min[0] + max[0]) / 2., (min[1] + max[1]) / 2.];
+        projection.cube_to_sphere(&face_coords_to_unit_cube_point(self.face(), coords))
+    }
+
+    fn lsb(&self) -> u64 {
+        self.0 & self.0.wrapping_neg()
+    }
+
+    fn lsb_for_level(level: u8) -> u64 {
+        1 << (2 * (CellId::MAX_LEVEL - level) as u32)
+    }
+}
+
+const _: () = assert!(FACE_BITS + POS_BITS == 64);
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use crate::vector::dot;
+use crate::{
+    face_coords_to_unit_cube_point, insert_attributes, sphere_point_to_face_coords, CubeFace,
+    CubeSphere, MeshData, VertexInfo,
+};
+
+/// the part of the sphere kept by [`CubeSphere::clip`], measured on the
+/// direction of every vertex from the center of the sphere
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SphereClip {
+    /// the directions within an angle of a center direction
+    Cap {
+        /// the direction of the center of the cap, any non-zero length
+        center: [f32; 3],
+        /// the angle between the center and the boundary, in radians, range
+        /// of `[0, PI]`, `PI / 2` for a hemisphere
+        angle: f32,
+    },
+    /// the directions between two latitudes, the `y` axis pointing to the
+    /// north pole
+    LatitudeBand {
+        /// the latitude of the southern boundary, in radians, range of `[-PI / 2, PI / 2]`
+        min: f32,
+        /// the latitude of the northern boundary, in radians, range of `[-PI / 2, PI / 2]`
+        max: f32,
+    },
+}
+
+impl SphereClip {
+    /// the half of the sphere around a direction, e.g. `[0., 1., 0.]` for a dome
+    pub fn hemisphere(center: [f32; 3]) -> Self {
+        SphereClip::Cap {
+            center,
+            angle: FRAC_PI_2,
+        }
+    }
+
+    /// check that the angles of the clip are within their range, and that the
+    /// center of a cap is finite and non-zero
+    pub(crate) fn is_valid(&self) -> bool {
+        match self {
+            SphereClip::Cap { center, angle } => {
+                center.iter().all(|c| c.is_finite())
+                    && center.iter().any(|c| *c != 0.)
+                    && (0. ..=PI).contains(angle)
+            }
+            SphereClip::LatitudeBand { min, max } => {
+                -FRAC_PI_2 <= *min && min <= max && *max <= FRAC_PI_2
+            }
+        }
+    }
+
+    /// the half-spaces `dir · normal >= offset` whose intersection is kept,
+    /// the ones keeping the whole sphere are left out
+    fn planes(&self) -> Vec<([f64; 3], f64)> {
+        // `PI` and `PI / 2` are rounded in `f32`, so the bounds of the ranges
+        // are compared to the angles rather than through their cosine
+        let planes = match *self {
+            SphereClip::Cap { angle, .. } if angle >= PI => vec![],
+            SphereClip::Cap { center, angle } => {
+                let center = center.map(f64::from);
+                let norm = dot(&center, &center).sqrt();
+                vec![(center.map(|c| c / norm), f64::from(angle).cos())]
+            }
+            SphereClip::LatitudeBand { min, max } => [
+                (min > -FRAC_PI_2).then(|| ([0., 1., 0.], f64::from(min).sin())),
+                (max < FRAC_PI_2).then(|| ([0., -1., 0.], -f64::from(max).sin())),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+        };
+        planes
+            .into_iter()
+            .filter(|(_, offset)| *offset > -1.)
+            .collect()
+    }
+}
+
+/// the number of halvings locating a boundary vertex along a cut edge, down
+/// to the precision of `f64`
+const CUT_ITERATIONS: u32 = 52;
+
+/// cut the triangles of the sphere along the boundary of a clip, the
+/// triangles crossing it become one or two triangles on the kept side
+/// ## Arguments
+/// - `sphere` - the sphere being generated
+/// - `clip` - the part of the sphere to keep
+/// - `step` - the angle between the samples of the displaced normal
+/// - `data` - the triangles, the boundary vertices are appended to them
+/// - `directions` - the unit sphere point of every vertex, extended with the
+///   boundary vertices
+/// - `faces` - the face of every vertex, extended with the boundary vertices
+pub(crate) fn clip(
+    sphere: &CubeSphere,
+    clip: &SphereClip,
+    step: f32,
+    data: &mut MeshData,
+    directions: &mut Vec<[f32; 3]>,
+    faces: &mut Vec<CubeFace>,
+) {
+    for (normal, offset) in clip.planes() {
+        // measured as in `cut_direction`, so that both agree on the side of
+        // the vertices lying on the plane up to rounding
+        let side: Vec<f64> = directions
+            .iter()
+            .map(|d| {
+                let d = d.map(f64::from);
+                dot(&d, &normal) / dot(&d, &d).sqrt() - offset
+            })
+            .collect();
+
+        // the boundary vertex of every cut edge, shared by the two triangles
+        // of the edge
+        let mut cuts: HashMap<(u32, u32), u32> = HashMap::new();
+        let triangles = std::mem::take(&mut data.indices);
+        let mut polygon = Vec::with_capacity(4);
+
+        for triangle in triangles.chunks(3) {
+            polygon.clear();
+            for k in 0..3 {
+                let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+                let (side_a, side_b) = (side[a as usize], side[b as usize]);
+                if side_a >= 0. {
+                    polygon.push(a);
+                }
+                if (side_a > 0. && side_b < 0.) || (side_a < 0. && side_b > 0.) {
+                    let key = (a.min(b), a.max(b));
+                    let cut = *cuts.entry(key).or_insert_with(|| {
+                        let direction = cut_direction(
+                            &directions[a as usize],
+                            &directions[b as usize],
+                            &normal,
+                            offset,
+                        );
+                        let face = faces[a as usize];
+                        insert_cut_vertex(sphere, step, direction, face, data, directions, faces)
+                    });
+                    polygon.push(cut);
+                }
+            }
+
+            // a fan over the kept part, a triangle or a quad, in the winding
+            // of the original triangle
+            for k in 1..polygon.len().saturating_sub(1) {
+                let (a, b, c) = (polygon[0], polygon[k], polygon[k + 1]);
+                if a != b && b != c && c != a {
+                    data.indices.extend([a, b, c]);
+                }
+            }
+        }
+    }
+}
+
+/// append a vertex added along a cut
+/// ## Arguments
+/// - `sphere` - the sphere being generated
+/// - `step` - the angle between the samples of the displaced normal
+/// - `direction` - the unit sphere point of the vertex
+/// - `face` - the face of the cut triangle, the attributes follow it
+/// - `data`, `directions`, `faces` - the vertices to append to
+/// ## Return
+/// - the index of the vertex
+pub(crate) fn insert_cut_vertex(
+    sphere: &CubeSphere,
+    step: f32,
+    direction: [f32; 3],
+    face: CubeFace,
+    data: &mut MeshData,
+    directions: &mut Vec<[f32; 3]>,
+    faces: &mut Vec<CubeFace>,
+) -> u32 {
+    // the cube point is found by inverting the projection
+    let (cube_face, coords) = sphere_point_to_face_coords(&direction, sphere.projection.as_ref());
+    let vertex = VertexInfo {
+        cube_point: face_coords_to_unit_cube_point(cube_face, coords),
+        sphere_point: direction,
+        face,
+        grid: None,
+    };
+    insert_attributes(&vertex, sphere, step, data);
+    directions.push(direction);
+    faces.push(face);
+    directions.len() as u32 - 1
+}
+
+/// the direction where the arc between two vertices crosses a plane
+/// ## Arguments
+/// - `a`, `b` - the directions of the vertices, on both sides of the plane
+/// - `normal`, `offset` - the plane `dir · normal = offset`
+/// ## Return
+/// - the unit direction on the plane, bit-identical whatever the order of
+///   `a` and `b`, so the faces sharing a cube edge cut it at the same point
+pub(crate) fn cut_direction(
+    a: &[f32; 3],
+    b: &[f32; 3],
+    normal: &[f64; 3],
+    offset: f64,
+) -> [f32; 3] {
+    let (from, to) = if a.map(f32::to_bits) <= b.map(f32::to_bits) {
+        (a.map(f64::from), b.map(f64::from))
+    } else {
+        (b.map(f64::from), a.map(f64::from))
+    };
+    let point = |t: f64| [0, 1, 2].map(|k| from[k] + t * (to[k] - from[k]));
+    let side = |t: f64| {
+        let p = point(t);
+        dot(&p, normal) / dot(&p, &p).sqrt() - offset
+    };
+
+    let from_inside = side(0.) >= 0.;
+    let (mut low, mut high) = (0., 1.);
+    for _ in 0..CUT_ITERATIONS {
+        let mid = 0.5 * (low + high);
+        if (side(mid) >= 0.) == from_inside {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    let p = point(0.5 * (low + high));
+    let norm = dot(&p, &p).sqrt();
+    p.map(|c| (c / norm) as f32)
+}
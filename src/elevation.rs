@@ -0,0 +1,76 @@
+use crate::vector::{cross, normalize, sub};
+
+/// a height offset of the surface above the radius of the sphere, e.g. the
+/// terrain of a planet
+///
+/// any closure `Fn(&[f32; 3]) -> f32` is an elevation
+pub trait Elevation: Send + Sync {
+    /// compute the height of the surface in a direction
+    /// ## Arguments
+    /// - `dir` - the direction from the center of the sphere, unit length
+    /// ## Return
+    /// - the offset along `dir`, added to the radius of the sphere
+    fn height(&self, dir: &[f32; 3]) -> f32;
+}
+
+impl<F> Elevation for F
+where
+    F: Fn(&[f32; 3]) -> f32 + Send + Sync,
+{
+    fn height(&self, dir: &[f32; 3]) -> f32 {
+        self(dir)
+    }
+}
+
+/// the smallest angle between the samples of [`surface_normal`], finer steps
+/// drown in the precision of `f32`
+const MIN_NORMAL_STEP: f32 = 1e-4;
+
+/// compute the normal of the displaced surface by central differences
+/// ## Arguments
+/// - `surface` - the point of the displaced surface in a direction
+/// - `dir` - the direction of the vertex, unit length
+/// - `step` - the angle between the samples, about half the grid spacing
+/// ## Return
+/// - the outward normal, unit length, the same for every vertex of the same
+///   direction whatever face it belongs to
+pub(crate) fn surface_normal(
+    surface: impl Fn(&[f32; 3]) -> [f32; 3],
+    dir: &[f32; 3],
+    step: f32,
+) -> [f32; 3] {
+    let step = step.max(MIN_NORMAL_STEP);
+
+    // a tangent frame depending on the direction only, `t1 × t2 = dir`
+    let abs = dir.map(f32::abs);
+    let mut reference = [0.; 3];
+    if abs[0] <= abs[1] && abs[0] <= abs[2] {
+        reference[0] = 1.;
+    } else if abs[1] <= abs[2] {
+        reference[1] = 1.;
+    } else {
+        reference[2] = 1.;
+    }
+    let t1 = normalize(&cross(&reference, dir));
+    let t2 = cross(dir, &t1);
+
+    let sample = |s: f32, t: f32| {
+        surface(&normalize(
+            &[0, 1, 2].map(|k| dir[k] + s * t1[k] + t * t2[k]),
+        ))
+    };
+    let along_t1 = sub(&sample(step, 0.), &sample(-step, 0.));
+    let along_t2 = sub(&sample(0., step), &sample(0., -step));
+
+    normalize(&cross(&along_t1, &along_t2))
+}
+
+/// make a tangent perpendicular to a normal, keeping its handedness
+/// ## Arguments
+/// - `tangent` - the tangent of the undisplaced surface
+/// - `normal` - the normal of the displaced surface, unit length
+pub(crate) fn orthogonalize(tangent: &[f32; 4], normal: &[f32; 3]) -> [f32; 4] {
+    let along = tangent[0] * normal[0] + tangent[1] * normal[1] + tangent[2] * normal[2];
+    let t = normalize(&[0, 1, 2].map(|k| tangent[k] - along * normal[k]));
+    [t[0], t[1], t[2], tangent[3]]
+}
@@ -0,0 +1,209 @@
+use std::ops::Range;
+
+use crate::vector::dot;
+
+/// a face of the cube the sphere is built from
+///
+/// every face is a grid of `resolution²` vertices, vertex `(i, j)` sits at
+/// `normal + a * u + b * v` on the unit cube with `a = 2 * i / (resolution - 1) - 1`
+/// and `b = 2 * j / (resolution - 1) - 1`, the grid is emitted with `i` in the
+/// outer loop and `j` in the inner loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CubeFace {
+    Front,
+    Back,
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// an edge of a cube face, named after the face coordinate that is fixed along it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaceEdge {
+    /// `a = -1`, the first row of the outer loop
+    MinU,
+    /// `a = 1`, the last row of the outer loop
+    MaxU,
+    /// `b = -1`, the first vertex of every inner loop
+    MinV,
+    /// `b = 1`, the last vertex of every inner loop
+    MaxV,
+}
+
+impl FaceEdge {
+    /// all edges, in the order used by [`CubeFace::neighbors`]
+    pub const ALL: [FaceEdge; 4] = [
+        FaceEdge::MinU,
+        FaceEdge::MaxU,
+        FaceEdge::MinV,
+        FaceEdge::MaxV,
+    ];
+
+    /// the position of the edge in [`FaceEdge::ALL`]
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// the direction pointing out of the face across this edge, in face coordinates
+    pub fn direction(self) -> [i32; 2] {
+        match self {
+            FaceEdge::MinU => [-1, 0],
+            FaceEdge::MaxU => [1, 0],
+            FaceEdge::MinV => [0, -1],
+            FaceEdge::MaxV => [0, 1],
+        }
+    }
+}
+
+/// the face on the other side of an edge, and how to carry coordinates over to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaceNeighbor {
+    /// the neighboring face
+    pub face: CubeFace,
+    /// the edge of the neighboring face shared with the original face
+    pub edge: FaceEdge,
+    /// the linear part of the coordinate transform
+    pub matrix: [[i32; 2]; 2],
+    /// the constant part of the coordinate transform
+    pub offset: [i32; 2],
+}
+
+impl FaceNeighbor {
+    /// convert coordinates of the original face to coordinates of the neighbor,
+    /// points past the shared edge are folded onto the neighbor as if the cube
+    /// was unfolded along that edge
+    /// ## Arguments
+    /// - `coords` - the coordinates along the original face, `[a, b]`
+    /// ## Return
+    /// - the coordinates along the neighboring face
+    pub fn transform(&self, coords: [f32; 2]) -> [f32; 2] {
+        let m = self.matrix.map(|row| row.map(|e| e as f32));
+        [
+            m[0][0] * coords[0] + m[0][1] * coords[1] + self.offset[0] as f32,
+            m[1][0] * coords[0] + m[1][1] * coords[1] + self.offset[1] as f32,
+        ]
+    }
+
+    /// same as [`FaceNeighbor::transform`] on coordinates scaled by an integer
+    /// ## Arguments
+    /// - `coords` - the scaled coordinates along the original face
+    /// - `scale` - the value of a scaled coordinate on the edge of the face
+    pub(crate) fn transform_scaled(&self, coords: [i64; 2], scale: i64) -> [i64; 2] {
+        let m = self.matrix.map(|row| row.map(i64::from));
+        let offset = self.offset.map(i64::from);
+        [
+            m[0][0] * coords[0] + m[0][1] * coords[1] + offset[0] * scale,
+            m[1][0] * coords[0] + m[1][1] * coords[1] + offset[1] * scale,
+        ]
+    }
+}
+
+impl CubeFace {
+    /// all faces, in the order they are emitted in the mesh
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::Front,
+        CubeFace::Back,
+        CubeFace::Left,
+        CubeFace::Right,
+        CubeFace::Top,
+        CubeFace::Bottom,
+    ];
+
+    /// the position of the face in [`CubeFace::ALL`]
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// the outward normal of the face
+    pub fn normal(self) -> [f32; 3] {
+        self.lattice_axes()[0].map(|c| c as f32)
+    }
+
+    /// the directions in which the face coordinates `a` and `b` grow, i.e.
+    /// the directions of the outer and inner loop of the grid
+    pub fn axes(self) -> [[f32; 3]; 2] {
+        let [_, u, v] = self.lattice_axes();
+        [u.map(|c| c as f32), v.map(|c| c as f32)]
+    }
+
+    /// the outward normal and the `u`, `v` axes of the face, `u × v = normal`
+    /// so the grid is counter-clockwise seen from outside
+    pub(crate) fn lattice_axes(self) -> [[i32; 3]; 3] {
+        match self {
+            CubeFace::Front => [[0, 0, 1], [1, 0, 0], [0, 1, 0]],
+            CubeFace::Back => [[0, 0, -1], [-1, 0, 0], [0, 1, 0]],
+            CubeFace::Left => [[-1, 0, 0], [0, 0, 1], [0, 1, 0]],
+            CubeFace::Right => [[1, 0, 0], [0, 0, -1], [0, 1, 0]],
+            CubeFace::Top => [[0, 1, 0], [1, 0, 0], [0, 0, -1]],
+            CubeFace::Bottom => [[0, -1, 0], [1, 0, 0], [0, 0, 1]],
+        }
+    }
+
+    /// the face whose outward normal is the given axis direction
+    pub(crate) fn from_normal(normal: [i32; 3]) -> CubeFace {
+        *CubeFace::ALL
+            .iter()
+            .find(|f| f.lattice_axes()[0] == normal)
+            .expect("not an axis direction")
+    }
+
+    /// the face on the other side of an edge
+    /// ## Arguments
+    /// - `edge` - the edge of this face
+    /// ## Return
+    /// - the neighboring face, its shared edge and the coordinate transform to it
+    pub fn neighbor(self, edge: FaceEdge) -> FaceNeighbor {
+        let [n, u, v] = self.lattice_axes();
+        let [da, db] = edge.direction();
+        let d = add(scale(u, da), scale(v, db));
+
+        let face = CubeFace::from_normal(d);
+        let [_, nu, nv] = face.lattice_axes();
+
+        // a point `n + a u + b v` at distance `s = (a, b) · (da, db)` across the
+        // edge is folded to `n + a u + b v - (s - 1) (d + n)`
+        let fold = |axis: [i32; 3]| {
+            let along_n = dot(&n, &axis);
+            [dot(&u, &axis) - da * along_n, dot(&v, &axis) - db * along_n]
+        };
+        let matrix = [fold(nu), fold(nv)];
+        let offset = [2 * dot(&n, &nu), 2 * dot(&n, &nv)];
+
+        let edge = match (dot(&n, &nu), dot(&n, &nv)) {
+            (-1, _) => FaceEdge::MinU,
+            (1, _) => FaceEdge::MaxU,
+            (_, -1) => FaceEdge::MinV,
+            _ => FaceEdge::MaxV,
+        };
+
+        FaceNeighbor {
+            face,
+            edge,
+            matrix,
+            offset,
+        }
+    }
+
+    /// the four neighboring faces, in the order of [`FaceEdge::ALL`]
+    pub fn neighbors(self) -> [FaceNeighbor; 4] {
+        FaceEdge::ALL.map(|edge| self.neighbor(edge))
+    }
+
+    /// the indices of the vertices of this face in a mesh generated without
+    /// `welded`
+    /// ## Arguments
+    /// - `resolution` - the resolution of the mesh
+    pub fn vertex_range(self, resolution: usize) -> Range<usize> {
+        let count = resolution.pow(2);
+        self.index() * count..(self.index() + 1) * count
+    }
+}
+
+fn add(a: [i32; 3], b: [i32; 3]) -> [i32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(a: [i32; 3], s: i32) -> [i32; 3] {
+    a.map(|c| c * s)
+}
@@ -0,0 +1,203 @@
+use crate::face::{CubeFace, FaceEdge};
+use crate::vector::dot;
+
+/// a vertex or a cell of the grid of one cube face
+///
+/// at a given resolution a face has `resolution²` vertices and
+/// `(resolution - 1)²` cells, cell `(i, j)` is the quad between the vertices
+/// `(i, j)` and `(i + 1, j + 1)`. Vertices on the edges of a face are shared
+/// with the neighboring faces, so the same vertex can be addressed from up to
+/// three faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridCoord {
+    /// the face of the grid
+    pub face: CubeFace,
+    /// the index along the outer loop of the face
+    pub i: u32,
+    /// the index along the inner loop of the face
+    pub j: u32,
+}
+
+/// the eight directions around a grid element, in face coordinates
+const DIRECTIONS_8: [[i64; 2]; 8] = [
+    [-1, 0],
+    [1, 0],
+    [0, -1],
+    [0, 1],
+    [-1, -1],
+    [-1, 1],
+    [1, -1],
+    [1, 1],
+];
+
+impl GridCoord {
+    /// address a vertex or a cell of a face
+    pub fn new(face: CubeFace, i: u32, j: u32) -> Self {
+        GridCoord { face, i, j }
+    }
+
+    /// the index of this vertex in a mesh generated without `welded`
+    /// ## Arguments
+    /// - `resolution` - the resolution of the mesh
+    pub fn vertex_index(&self, resolution: usize) -> usize {
+        self.face.vertex_range(resolution).start + self.i as usize * resolution + self.j as usize
+    }
+
+    /// the neighbors of this cell sharing an edge with it
+    /// ## Arguments
+    /// - `resolution` - the number of vertices on the edge of the cube
+    /// ## Return
+    /// - the neighbors, in the order of [`FaceEdge::ALL`]
+    pub fn cell_neighbors4(&self, resolution: usize) -> [GridCoord; 4] {
+        let m = resolution as i64 - 1;
+        let coords = self.scaled(m, 1);
+        FaceEdge::ALL.map(|edge| {
+            let (face, coords) = step(self.face, coords, edge.direction().map(i64::from), m);
+            GridCoord::from_scaled(face, coords, m, 1)
+        })
+    }
+
+    /// the neighbors of this cell sharing an edge or a corner with it, the
+    /// cells in the corners of a face have 7 neighbors as only three faces
+    /// meet at a corner of the cube
+    /// ## Arguments
+    /// - `resolution` - the number of vertices on the edge of the cube
+    pub fn cell_neighbors8(&self, resolution: usize) -> Vec<GridCoord> {
+        let m = resolution as i64 - 1;
+        let coords = self.scaled(m, 1);
+        let inside = |c: i64| c.abs() < m;
+
+        DIRECTIONS_8
+            .into_iter()
+            .filter_map(|[da, db]| {
+                // a diagonal step is taken as two straight steps, the one
+                // staying on this face first, so that at most one edge is crossed
+                let (first, second) = if inside(coords[0] + 2 * da) {
+                    ([da, 0], [0, db])
+                } else if inside(coords[1] + 2 * db) {
+                    ([0, db], [da, 0])
+                } else {
+                    return None;
+                };
+                let (face, coords) = step(self.face, coords, first, m);
+                let (face, coords) = step(face, coords, second, m);
+                Some(GridCoord::from_scaled(face, coords, m, 1))
+            })
+            .collect()
+    }
+
+    /// every address of this vertex, on this face first
+    /// ## Arguments
+    /// - `resolution` - the number of vertices on the edge of the cube
+    /// ## Return
+    /// - one coordinate inside a face, two on an edge, three on a corner of the cube
+    pub fn vertex_aliases(&self, resolution: usize) -> Vec<GridCoord> {
+        let m = resolution as i64 - 1;
+        let point = self.lattice_point(m);
+
+        let mut aliases = vec![*self];
+        for face in CubeFace::ALL {
+            let [n, u, v] = face.lattice_axes().map(|axis| axis.map(i64::from));
+            if face != self.face && dot(&point, &n) == m {
+                aliases.push(GridCoord::from_scaled(
+                    face,
+                    [dot(&point, &u), dot(&point, &v)],
+                    m,
+                    0,
+                ));
+            }
+        }
+        aliases
+    }
+
+    /// the vertices connected to this vertex by an edge of the grid, every
+    /// vertex has 4 of them except the 8 corners of the cube which have 3
+    /// ## Arguments
+    /// - `resolution` - the number of vertices on the edge of the cube
+    pub fn vertex_neighbors4(&self, resolution: usize) -> Vec<GridCoord> {
+        self.vertex_neighbors(resolution, &DIRECTIONS_8[..4])
+    }
+
+    /// the vertices sharing a cell with this vertex, every vertex has 8 of them
+    /// except the 8 corners of the cube which have 6
+    /// ## Arguments
+    /// - `resolution` - the number of vertices on the edge of the cube
+    pub fn vertex_neighbors8(&self, resolution: usize) -> Vec<GridCoord> {
+        self.vertex_neighbors(resolution, &DIRECTIONS_8)
+    }
+
+    /// collect the neighbors of a vertex within every face it belongs to, a
+    /// neighbor on several faces is reported once, on the first face it is found
+    fn vertex_neighbors(&self, resolution: usize, directions: &[[i64; 2]]) -> Vec<GridCoord> {
+        let m = resolution as i64 - 1;
+        let mut neighbors: Vec<GridCoord> = Vec::with_capacity(directions.len());
+        let mut seen: Vec<[i64; 3]> = Vec::with_capacity(directions.len());
+
+        for alias in self.vertex_aliases(resolution) {
+            let coords = alias.scaled(m, 0);
+            for [da, db] in directions {
+                let a = coords[0] + 2 * da;
+                let b = coords[1] + 2 * db;
+                if a.abs() > m || b.abs() > m {
+                    continue;
+                }
+
+                let neighbor = GridCoord::from_scaled(alias.face, [a, b], m, 0);
+                let point = neighbor.lattice_point(m);
+                if !seen.contains(&point) {
+                    seen.push(point);
+                    neighbors.push(neighbor);
+                }
+            }
+        }
+        neighbors
+    }
+
+    /// the face coordinates scaled so that the edges of the face are at `±m`
+    /// and neighboring elements are `2` apart
+    /// ## Arguments
+    /// - `m` - the number of cells on the edge of a face
+    /// - `center` - `0` for a vertex, `1` for the center of a cell
+    fn scaled(&self, m: i64, center: i64) -> [i64; 2] {
+        [
+            2 * self.i as i64 + center - m,
+            2 * self.j as i64 + center - m,
+        ]
+    }
+
+    fn from_scaled(face: CubeFace, coords: [i64; 2], m: i64, center: i64) -> Self {
+        GridCoord {
+            face,
+            i: ((coords[0] + m - center) / 2) as u32,
+            j: ((coords[1] + m - center) / 2) as u32,
+        }
+    }
+
+    /// the vertex on the cube scaled so that the faces are at `±m`
+    pub(crate) fn lattice_point(&self, m: i64) -> [i64; 3] {
+        let [n, u, v] = self.face.lattice_axes().map(|axis| axis.map(i64::from));
+        let [a, b] = self.scaled(m, 0);
+        [0, 1, 2].map(|k| m * n[k] + a * u[k] + b * v[k])
+    }
+}
+
+/// move one element along a face, continuing onto the neighboring face when
+/// an edge is crossed
+/// ## Arguments
+/// - `face` - the face of the element
+/// - `coords` - the scaled coordinates of the element
+/// - `direction` - the step, along at most one edge-crossing axis
+/// - `m` - the scaled coordinate of the edges of the face
+fn step(face: CubeFace, coords: [i64; 2], direction: [i64; 2], m: i64) -> (CubeFace, [i64; 2]) {
+    let moved = [coords[0] + 2 * direction[0], coords[1] + 2 * direction[1]];
+    let edge = match moved {
+        [a, _] if a > m => FaceEdge::MaxU,
+        [a, _] if a < -m => FaceEdge::MinU,
+        [_, b] if b > m => FaceEdge::MaxV,
+        [_, b] if b < -m => FaceEdge::MinV,
+        _ => return (face, moved),
+    };
+
+    let neighbor = face.neighbor(edge);
+    (neighbor.face, neighbor.transform_scaled(moved, m))
+}
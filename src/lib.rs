@@ -1,200 +1,924 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::f32::consts::FRAC_PI_4;
+use std::fmt;
+use std::sync::Arc;
+
 use bevy::prelude::Mesh;
-use bevy::render::mesh::Indices;
+use bevy::render::mesh::{Indices, MeshVertexAttribute};
 use bevy::render::render_resource::PrimitiveTopology;
-use float_ord::FloatOrd;
+
+use attribute::AttributeColumn;
+use vector::{cross, dot, length, normalize};
+
+mod antimeridian;
+mod attribute;
+mod cell;
+mod clip;
+mod elevation;
+mod face;
+mod grid;
+mod lod;
+pub mod noise;
+mod normals;
+pub mod projection;
+pub mod uv;
+mod vector;
+mod wireframe;
+
+pub use attribute::{
+    AttributeHook, AttributeValue, VertexInfo, ATTRIBUTE_CUBE_FACE, ATTRIBUTE_CUBE_POINT,
+};
+pub use cell::CellId;
+pub use clip::SphereClip;
+pub use elevation::Elevation;
+pub use face::{CubeFace, FaceEdge, FaceNeighbor};
+pub use grid::GridCoord;
+pub use lod::{LodQuadtree, LodSeams, LodSettings, LodUpdate, Viewpoint};
+pub use noise::NoiseSettings;
+pub use projection::CubeProjection;
+pub use uv::UvLayout;
+pub use wireframe::GridLines;
 
 /// a cube sphere
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone)]
 pub struct CubeSphere {
     /// the radius of the sphere
     pub radius: f32,
-    /// the number of vertices on the edge of the cube.
+    /// the radii along the `x`, `y` and `z` axes to make an ellipsoid, in
+    /// place of `radius`
+    pub radii: Option<[f32; 3]>,
+    /// the number of vertices on the edge of the cube, the mesh has up to
+    /// `6 * resolution²` vertices
     pub resolution: usize,
+    /// share the vertices along the cube edges and corners between the faces,
+    /// the copies of a vertex with the same position, normal and custom
+    /// attributes, e.g. [`ATTRIBUTE_CUBE_FACE`] or the values of the hook,
+    /// become one. The mesh has no uv and no tangent then, the uv of a border
+    /// vertex differs per face
+    pub welded: bool,
+    /// how the surface of the cube is mapped onto the sphere
+    pub projection: Arc<dyn CubeProjection>,
+    /// displace every vertex to `radius + height(dir)`, the normals and
+    /// tangents then follow the displaced surface
+    pub elevation: Option<Arc<dyn Elevation>>,
+    /// keep only a part of the sphere, the triangles crossing the boundary
+    /// are cut along it. Applies to [`CubeSphere::try_mesh`] and
+    /// [`CubeSphere::try_meshes`], not to the patches
+    pub clip: Option<SphereClip>,
+    /// face the inside of the sphere, e.g. for a sky, the winding is reversed,
+    /// the normals point to the center and `u` is mirrored, see
+    /// [`UvLayout::mirror`], so that textures read the right way from the inside
+    pub inverted: bool,
+    /// how the faces are arranged in the texture, for the uv coordinates of
+    /// the mesh and the uv helpers such as [`CubeSphere::uv_to_point`]
+    pub uv_layout: Arc<dyn UvLayout>,
+    /// compute extra attributes for every vertex, see [`AttributeHook`]
+    pub attribute_hook: Option<Arc<dyn AttributeHook>>,
+    /// write the face and the cube point of every vertex for shaders, in
+    /// [`ATTRIBUTE_CUBE_FACE`] and [`ATTRIBUTE_CUBE_POINT`]
+    pub cube_attributes: bool,
 }
 
 impl Default for CubeSphere {
     fn default() -> Self {
         CubeSphere {
             radius: 1.0,
+            radii: None,
             resolution: 8,
+            welded: false,
+            projection: Arc::new(projection::Spherified),
+            elevation: None,
+            clip: None,
+            inverted: false,
+            uv_layout: Arc::new(uv::DiscPacking),
+            attribute_hook: None,
+            cube_attributes: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-enum CubeFace {
-    Front,
-    Back,
-    Left,
-    Right,
-    Top,
-    Bottom,
+impl fmt::Debug for CubeSphere {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CubeSphere")
+            .field("radius", &self.radius)
+            .field("radii", &self.radii)
+            .field("resolution", &self.resolution)
+            .field("welded", &self.welded)
+            .field("projection", &self.projection)
+            .field("elevation", &self.elevation.as_ref().map(|_| ".."))
+            .field("clip", &self.clip)
+            .field("inverted", &self.inverted)
+            .field("uv_layout", &self.uv_layout)
+            .field(
+                "attribute_hook",
+                &self.attribute_hook.as_ref().map(|_| ".."),
+            )
+            .field("cube_attributes", &self.cube_attributes)
+            .finish()
+    }
 }
 
 const CUBE_FACE_COUNT: usize = 6;
 
+/// the flattening `(a - b) / a` of the WGS84 reference ellipsoid of the earth
+pub const WGS84_FLATTENING: f32 = (1. / 298.257_223_563_f64) as f32;
+
+/// the maximum number of vertices in a generated mesh, the largest count
+/// addressable by 32 bit indices. It bounds the index width only, a mesh
+/// near it would take hundreds of gigabytes
+const MAX_VERTEX_COUNT: usize = u32::MAX as usize;
+
+/// how a patch of [`CubeSphere::try_patch_mesh`] hides the cracks along the
+/// borders with coarser patches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PatchSeam {
+    /// leave the borders as they are
+    None,
+    /// skip every other vertex along some edges so that they match a patch of
+    /// the level above, bit `k` of the mask stitches the edge `FaceEdge::ALL[k]`
+    Stitch(u8),
+    /// hang a strip of triangles below every edge, down to this distance
+    /// towards the center of the sphere, finite and not negative
+    Skirt(f32),
+}
+
+/// the reason a cube sphere cannot be turned into a mesh
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CubeSphereError {
+    /// the resolution is smaller than `2`
+    InvalidResolution(usize),
+    /// the radius is not a finite positive number
+    InvalidRadius(f32),
+    /// the resolution needs more vertices than 32 bit indices can address
+    TooManyVertices(usize),
+    /// the vertex budget of a split mesh cannot hold a single triangle
+    InvalidVertexBudget(usize),
+    /// the level is deeper than [`CellId::MAX_LEVEL`]
+    InvalidLevel(u8),
+    /// the resolution of a stitched patch is even, so a coarser neighbor
+    /// does not share every other vertex of its edge
+    InvalidStitchResolution(usize),
+    /// the mesh is not a triangle list with positions and indices
+    IncompatibleMesh(usize),
+    /// an angle of the clip is out of its range, the band ends below its
+    /// start, or the center of the cap is not finite or zero
+    InvalidClip(SphereClip),
+    /// the spacing between the grid lines of a wireframe is `0`
+    InvalidLineSpacing(usize),
+    /// the attribute of a hook is not a 32 bit format, or a value of the
+    /// hook does not match it
+    InvalidAttribute(&'static str),
+    /// the gutter of [`uv::FaceTiles`] is not in the range of `[0, 0.5)`
+    InvalidGutter(f32),
+    /// the depth of the skirts of a patch is negative or not finite
+    InvalidSkirtDepth(f32),
+}
+
+impl fmt::Display for CubeSphereError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CubeSphereError::InvalidResolution(resolution) => write!(
+                f,
+                "Cannot create a cube sphere with resolution {}. (At least 2 vertices per edge)",
+                resolution
+            ),
+            CubeSphereError::InvalidRadius(radius) => write!(
+                f,
+                "Cannot create a cube sphere with radius {}. (Must be finite and positive)",
+                radius
+            ),
+            CubeSphereError::TooManyVertices(resolution) => write!(
+                f,
+                "Cannot create a cube sphere with resolution {}. (Limited to {} vertices)",
+                resolution, MAX_VERTEX_COUNT
+            ),
+            CubeSphereError::InvalidVertexBudget(max_vertices) => write!(
+                f,
+                "Cannot split a cube sphere into meshes of {} vertices. (At least 3 vertices)",
+                max_vertices
+            ),
+            CubeSphereError::InvalidLevel(level) => write!(
+                f,
+                "Cannot split a cube sphere down to level {}. (At most {} levels)",
+                level,
+                CellId::MAX_LEVEL
+            ),
+            CubeSphereError::InvalidStitchResolution(resolution) => write!(
+                f,
+                "Cannot stitch patches with resolution {}. (Must be odd)",
+                resolution
+            ),
+            CubeSphereError::IncompatibleMesh(count) => write!(
+                f,
+                "Cannot recompute the normals of a mesh of {} vertices. (Needs positions and triangle indices)",
+                count
+            ),
+            CubeSphereError::InvalidClip(clip) => write!(
+                f,
+                "Cannot clip a cube sphere to {:?}. (Angles must be in range, around a finite non-zero center)",
+                clip
+            ),
+            CubeSphereError::InvalidLineSpacing(spacing) => write!(
+                f,
+                "Cannot draw every {}th grid line of a cube sphere. (At least 1)",
+                spacing
+            ),
+            CubeSphereError::InvalidAttribute(name) => write!(
+                f,
+                "Cannot write the attribute {} of a cube sphere. (Values must match a 32 bit format)",
+                name
+            ),
+            CubeSphereError::InvalidGutter(gutter) => write!(
+                f,
+                "Cannot lay out the faces of a cube sphere with a gutter of {}. (Range of [0, 0.5))",
+                gutter
+            ),
+            CubeSphereError::InvalidSkirtDepth(depth) => write!(
+                f,
+                "Cannot hang skirts of depth {} below a patch. (Must be finite and not negative)",
+                depth
+            ),
+        }
+    }
+}
+
+impl Error for CubeSphereError {}
+
 impl From<CubeSphere> for Mesh {
     fn from(sphere: CubeSphere) -> Self {
-        if sphere.resolution < 2 || sphere.resolution > 103 {
-            panic!(
-                "Cannot create an cube sphere with resolution {}. (Limited to 65535 vertices)",
-                sphere.resolution
-            );
-        }
-
-        let total_point_count = CUBE_FACE_COUNT * sphere.resolution.pow(2);
-        let mut points: Vec<[f32; 3]> = Vec::with_capacity(total_point_count);
-        let mut normals: Vec<[f32; 3]> = Vec::with_capacity(total_point_count);
-        let mut tangents: Vec<[f32; 4]> = Vec::with_capacity(total_point_count);
-        let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(total_point_count);
-
-        let n = sphere.resolution as u32;
-        let sep = 2_f32 / (n as f32 - 1.);
-
-        // Front
-        for ix in 0..n {
-            let x = (ix as f32) * sep - 1.;
-            for iy in 0..n {
-                let y = (iy as f32) * sep - 1.;
-
-                let unit_cube_point = [x, y, 1.];
-                insert_attributes(
-                    &unit_cube_point,
-                    sphere.radius,
-                    CubeFace::Front,
-                    &mut points,
-                    &mut normals,
-                    &mut tangents,
-                    &mut uvs,
-                );
+        sphere.try_mesh().unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl CubeSphere {
+    /// an oblate spheroid, flattened along the `y` axis like a spinning planet
+    /// ## Arguments
+    /// - `equatorial_radius` - the radius along the `x` and `z` axes
+    /// - `flattening` - how much shorter the polar radius is, relative to the
+    ///   equatorial one, e.g. [`WGS84_FLATTENING`]
+    pub fn oblate(equatorial_radius: f32, flattening: f32) -> Self {
+        let polar_radius = equatorial_radius * (1. - flattening);
+        CubeSphere {
+            radius: equatorial_radius,
+            radii: Some([equatorial_radius, polar_radius, equatorial_radius]),
+            ..Default::default()
+        }
+    }
+
+    /// check that the sphere can be turned into a mesh
+    /// ## Return
+    /// - the number of vertices of the mesh generated without `welded`
+    fn validate(&self) -> Result<usize, CubeSphereError> {
+        if let Some(clip) = self.clip.filter(|c| !c.is_valid()) {
+            return Err(CubeSphereError::InvalidClip(clip));
+        }
+        self.validate_grids(self.resolution, CUBE_FACE_COUNT)
+    }
+
+    /// the attributes added to the generated ones, the cube attributes
+    /// first, then the ones of the hook, checked to be supported
+    fn custom_attributes(&self) -> Result<Vec<MeshVertexAttribute>, CubeSphereError> {
+        let mut attributes = Vec::new();
+        if self.cube_attributes {
+            attributes.extend([ATTRIBUTE_CUBE_FACE, ATTRIBUTE_CUBE_POINT]);
+        } else if self.uv_layout.face_attribute() {
+            attributes.push(ATTRIBUTE_CUBE_FACE);
+        }
+        if let Some(hook) = &self.attribute_hook {
+            attributes.extend(hook.attributes());
+        }
+
+        match attributes
+            .iter()
+            .find(|a| AttributeValue::zero(a.format).is_none())
+        {
+            Some(attribute) => Err(CubeSphereError::InvalidAttribute(attribute.name)),
+            None => Ok(attributes),
+        }
+    }
+
+    /// check that some square grids of the sphere can be turned into a mesh
+    /// ## Arguments
+    /// - `resolution` - the number of vertices on the edge of a grid
+    /// - `grid_count` - the number of grids in the mesh
+    /// ## Return
+    /// - the number of vertices of the mesh
+    fn validate_grids(
+        &self,
+        resolution: usize,
+        grid_count: usize,
+    ) -> Result<usize, CubeSphereError> {
+        if resolution < 2 {
+            return Err(CubeSphereError::InvalidResolution(resolution));
+        }
+        let radii = self.radii.unwrap_or([self.radius; 3]);
+        if let Some(radius) = radii.into_iter().find(|r| !r.is_finite() || *r <= 0.) {
+            return Err(CubeSphereError::InvalidRadius(radius));
+        }
+        self.uv_layout.validate()?;
+
+        resolution
+            .checked_pow(2)
+            .and_then(|c| c.checked_mul(grid_count))
+            .filter(|c| *c <= MAX_VERTEX_COUNT)
+            .ok_or(CubeSphereError::TooManyVertices(resolution))
+    }
+
+    /// generate the mesh of the sphere, with 16 bit indices whenever the
+    /// vertex count allows it
+    /// ## Return
+    /// - the mesh, or the reason it cannot be generated
+    pub fn try_mesh(&self) -> Result<Mesh, CubeSphereError> {
+        self.mesh_data()?.into_mesh(PrimitiveTopology::TriangleList)
+    }
+
+    /// generate the sphere as several meshes, so that each of them stays under
+    /// a vertex budget, e.g. `65536` to keep 16 bit indices on every mesh
+    ///
+    /// every mesh covers a block of the grid of a face and is generated on
+    /// its own, the sphere is never held whole. The blocks copy the vertices
+    /// of their common borders, so `welded` shares nothing between meshes
+    /// ## Arguments
+    /// - `max_vertices` - the maximum number of vertices in each mesh, at least `3`
+    /// ## Return
+    /// - the meshes, or the reason they cannot be generated
+    pub fn try_meshes(&self, max_vertices: usize) -> Result<Vec<Mesh>, CubeSphereError> {
+        if max_vertices < 3 {
+            return Err(CubeSphereError::InvalidVertexBudget(max_vertices));
+        }
+        self.validate()?;
+
+        let mut meshes = Vec::new();
+        for block in self.split_blocks(max_vertices) {
+            // the cuts of a clip may add vertices past the budget of a block
+            for data in self.build_mesh_data(true, &[block])?.split(max_vertices) {
+                meshes.push(data.into_mesh(PrimitiveTopology::TriangleList)?);
             }
         }
-        // Back
-        for ix in 0..n {
-            let x = 1. - (ix as f32) * sep;
-            for iy in 0..n {
-                let y = (iy as f32) * sep - 1.;
-
-                let unit_cube_point = [x, y, -1.];
-                insert_attributes(
-                    &unit_cube_point,
-                    sphere.radius,
-                    CubeFace::Back,
-                    &mut points,
-                    &mut normals,
-                    &mut tangents,
-                    &mut uvs,
-                );
+        Ok(meshes)
+    }
+
+    /// generate the mesh of a single cell of a face, the vertices on the
+    /// border of the cell are bit-identical to the ones of the neighboring
+    /// cells of the same level or of a level with a multiple of its grid,
+    /// `welded` is ignored as a patch never crosses a cube edge
+    /// ## Arguments
+    /// - `cell` - the part of the face to generate
+    /// - `resolution` - the number of vertices on the edge of the patch
+    /// - `seam` - how to hide the cracks along the borders with coarser patches
+    /// ## Return
+    /// - the mesh, or the reason it cannot be generated
+    pub fn try_patch_mesh(
+        &self,
+        cell: CellId,
+        resolution: usize,
+        seam: PatchSeam,
+    ) -> Result<Mesh, CubeSphereError> {
+        self.patch_data(cell, resolution, seam)?
+            .into_mesh(PrimitiveTopology::TriangleList)
+    }
+
+    /// check that the patches of the sphere can be turned into meshes
+    /// ## Arguments
+    /// - `resolution` - the number of vertices on the edge of a patch
+    /// - `seam` - how the patches hide the cracks along their borders
+    /// ## Return
+    /// - the number of vertices of a patch
+    pub(crate) fn validate_patch(
+        &self,
+        resolution: usize,
+        seam: PatchSeam,
+    ) -> Result<usize, CubeSphereError> {
+        let grid_point_count = self.validate_grids(resolution, 1)?;
+        self.custom_attributes()?;
+        match seam {
+            PatchSeam::None => Ok(grid_point_count),
+            PatchSeam::Stitch(_) if resolution & 1 == 0 => {
+                Err(CubeSphereError::InvalidStitchResolution(resolution))
             }
+            PatchSeam::Stitch(_) => Ok(grid_point_count),
+            PatchSeam::Skirt(depth) if !depth.is_finite() || depth < 0. => {
+                Err(CubeSphereError::InvalidSkirtDepth(depth))
+            }
+            PatchSeam::Skirt(_) => grid_point_count
+                .checked_add(4 * (resolution - 1))
+                .filter(|c| *c <= MAX_VERTEX_COUNT)
+                .ok_or(CubeSphereError::TooManyVertices(resolution)),
         }
-        // Left
-        for iz in 0..n {
-            let z = (iz as f32) * sep - 1.;
-            for iy in 0..n {
-                let y = (iy as f32) * sep - 1.;
-
-                let unit_cube_point = [-1., y, z];
-                insert_attributes(
-                    &unit_cube_point,
-                    sphere.radius,
-                    CubeFace::Left,
-                    &mut points,
-                    &mut normals,
-                    &mut tangents,
-                    &mut uvs,
-                );
+    }
+
+    /// generate the vertex attributes and the indices of a single cell of a face
+    fn patch_data(
+        &self,
+        cell: CellId,
+        resolution: usize,
+        seam: PatchSeam,
+    ) -> Result<MeshData, CubeSphereError> {
+        let point_count = self.validate_patch(resolution, seam)?;
+        let mut data = MeshData::with_capacity(point_count, self.custom_attributes()?);
+
+        let face = cell.face();
+        let [ci, cj] = cell.ij();
+        let edge_count = resolution as i64 - 1;
+        let m = edge_count << cell.level();
+        let step = FRAC_PI_4 / m as f32;
+
+        let mut directions = Vec::with_capacity(point_count);
+        for i in 0..resolution as i64 {
+            for j in 0..resolution as i64 {
+                // the coordinates are exact ratios of integers, rounded once,
+                // so every patch sharing a vertex computes the same point, on
+                // any face
+                let global = [ci as i64 * edge_count + i, cj as i64 * edge_count + j];
+                let coords = global.map(|g| ((2 * g - m) as f64 / m as f64) as f32);
+                let cube_point = face_coords_to_unit_cube_point(face, coords);
+                let vertex = VertexInfo {
+                    cube_point,
+                    sphere_point: self.projection.cube_to_sphere(&cube_point),
+                    face,
+                    grid: Some([i as u32, j as u32]),
+                };
+                insert_attributes(&vertex, self, step, &mut data);
+                directions.push(vertex.sphere_point);
             }
         }
-        // Right
-        for iz in 0..n {
-            let z = 1. - (iz as f32) * sep;
-            for iy in 0..n {
-                let y = (iy as f32) * sep - 1.;
-
-                let unit_cube_point = [1., y, z];
-                insert_attributes(
-                    &unit_cube_point,
-                    sphere.radius,
-                    CubeFace::Right,
-                    &mut points,
-                    &mut normals,
-                    &mut tangents,
-                    &mut uvs,
-                );
+
+        let n = resolution as u32;
+        let MeshData {
+            points,
+            normals,
+            tangents,
+            uvs,
+            custom,
+            indices,
+        } = &mut data;
+        indices.reserve(6 * (resolution - 1).pow(2));
+        match seam {
+            PatchSeam::None => insert_grid_indices(n, 0, indices),
+            PatchSeam::Stitch(mask) => insert_stitched_grid_indices(n, mask, indices),
+            PatchSeam::Skirt(depth) => {
+                insert_grid_indices(n, 0, indices);
+
+                // the border of the grid, counter-clockwise seen from outside
+                let last = n - 1;
+                let border: Vec<u32> = (0..last)
+                    .map(|i| i * n)
+                    .chain((0..last).map(|j| last * n + j))
+                    .chain((1..n).rev().map(|i| i * n + last))
+                    .chain((1..n).rev())
+                    .collect();
+
+                let first_bottom = points.len() as u32;
+                for top in &border {
+                    let point = points[*top as usize];
+                    let norm = length(&point);
+                    points.push(point.map(|c| c * (1. - depth / norm)));
+                    normals.push(normals[*top as usize]);
+                    tangents.push(tangents[*top as usize]);
+                    uvs.push(uvs[*top as usize]);
+                    for column in custom.iter_mut() {
+                        column.values.push(column.values[*top as usize]);
+                    }
+                    directions.push(directions[*top as usize]);
+                }
+
+                for k in 0..border.len() {
+                    let next = (k + 1) % border.len();
+                    let (top, next_top) = (border[k], border[next]);
+                    let (bottom, next_bottom) =
+                        (first_bottom + k as u32, first_bottom + next as u32);
+
+                    // facing away from the patch
+                    indices.extend([top, bottom, next_top]);
+                    indices.extend([next_top, bottom, next_bottom]);
+                }
             }
         }
-        // Top
-        for ix in 0..n {
-            let x = (ix as f32) * sep - 1.;
-            for iz in 0..n {
-                let z = 1. - (iz as f32) * sep;
-
-                let unit_cube_point = [x, 1., z];
-                insert_attributes(
-                    &unit_cube_point,
-                    sphere.radius,
-                    CubeFace::Top,
-                    &mut points,
-                    &mut normals,
-                    &mut tangents,
-                    &mut uvs,
-                );
+
+        if self.uv_layout.antimeridian() {
+            let mut faces = vec![face; directions.len()];
+            antimeridian::wrap(self, step, &mut data, &mut directions, &mut faces);
+            data = data.compact();
+        }
+        if self.inverted {
+            data.flip_winding();
+        }
+
+        Ok(data)
+    }
+
+    /// generate the vertex attributes and the indices of the sphere
+    fn mesh_data(&self) -> Result<MeshData, CubeSphereError> {
+        self.build_mesh_data(true, &self.face_blocks())
+    }
+
+    /// the whole grid of every face, in the order of `CubeFace::ALL`
+    fn face_blocks(&self) -> Vec<GridBlock> {
+        let n = self.resolution as u32;
+        CubeFace::ALL
+            .into_iter()
+            .map(|face| GridBlock {
+                face,
+                first: [0, 0],
+                size: [n, n],
+            })
+            .collect()
+    }
+
+    /// split the grid of every face into blocks of at most some vertices
+    /// ## Arguments
+    /// - `max_vertices` - the maximum number of vertices in each block, the
+    ///   blocks have at least `2 × 2` vertices whatever the budget
+    fn split_blocks(&self, max_vertices: usize) -> Vec<GridBlock> {
+        let n = self.resolution;
+        let edge_count = n - 1;
+
+        // the fewest blocks along an axis with at most some vertices each,
+        // spread evenly, the last one may be smaller
+        let edges_per_block = |max_size: usize| {
+            let block_count = edge_count.div_ceil(max_size - 1);
+            edge_count.div_ceil(block_count)
+        };
+        let columns = edges_per_block(((max_vertices as f64).sqrt() as usize).clamp(2, n));
+        let rows = edges_per_block((max_vertices / (columns + 1)).clamp(2, n));
+
+        // neighboring blocks share the vertices of their common border
+        let starts = |edges: usize| {
+            (0..edge_count)
+                .step_by(edges)
+                .map(move |s| (s, (edges + 1).min(n - s)))
+        };
+        let mut blocks = Vec::new();
+        for face in CubeFace::ALL {
+            for (i, block_rows) in starts(rows) {
+                for (j, block_columns) in starts(columns) {
+                    blocks.push(GridBlock {
+                        face,
+                        first: [i as u32, j as u32],
+                        size: [block_rows as u32, block_columns as u32],
+                    });
+                }
             }
         }
-        // Bottom
-        for ix in 0..n {
-            let x = (ix as f32) * sep - 1.;
-            for iz in 0..n {
-                let z = (iz as f32) * sep - 1.;
-
-                let unit_cube_point = [x, -1., z];
-                insert_attributes(
-                    &unit_cube_point,
-                    sphere.radius,
-                    CubeFace::Bottom,
-                    &mut points,
-                    &mut normals,
-                    &mut tangents,
-                    &mut uvs,
-                );
+        blocks
+    }
+
+    /// generate the vertex attributes and the indices of some blocks of the
+    /// face grids
+    /// ## Arguments
+    /// - `cut` - whether to clip the sphere and to cut it along the antimeridian,
+    ///   without it the vertices are the ones of the blocks, in order
+    /// - `blocks` - the blocks of the face grids to triangulate
+    fn build_mesh_data(
+        &self,
+        cut: bool,
+        blocks: &[GridBlock],
+    ) -> Result<MeshData, CubeSphereError> {
+        self.validate()?;
+        let point_count = blocks
+            .iter()
+            .map(|b| (b.size[0] * b.size[1]) as usize)
+            .sum();
+        let mut data = MeshData::with_capacity(point_count, self.custom_attributes()?);
+
+        let n = self.resolution as u32;
+        let step = FRAC_PI_4 / (n - 1) as f32;
+
+        let mut directions = Vec::with_capacity(point_count);
+        let mut faces = Vec::with_capacity(point_count);
+        for block in blocks {
+            let face = block.face;
+            let offset = data.points.len() as u32;
+            for i in block.first[0]..block.first[0] + block.size[0] {
+                for j in block.first[1]..block.first[1] + block.size[1] {
+                    let cube_point = unit_cube_grid_point(face, i, j, n);
+                    let vertex = VertexInfo {
+                        cube_point,
+                        sphere_point: self.projection.cube_to_sphere(&cube_point),
+                        face,
+                        grid: Some([i, j]),
+                    };
+                    insert_attributes(&vertex, self, step, &mut data);
+                    directions.push(vertex.sphere_point);
+                    faces.push(face);
+                }
+            }
+
+            // construct faces
+            let [rows, columns] = block.size;
+            data.indices
+                .reserve(6 * ((rows - 1) * (columns - 1)) as usize);
+            insert_block_indices(rows, columns, offset, &mut data.indices);
+        }
+
+        let clipped = cut && self.clip.is_some();
+        if let (true, Some(clip)) = (cut, &self.clip) {
+            clip::clip(self, clip, step, &mut data, &mut directions, &mut faces);
+        }
+        // a welded mesh has no uv to wrap
+        let wrapped = cut && self.uv_layout.antimeridian() && !self.welded;
+        if wrapped {
+            antimeridian::wrap(self, step, &mut data, &mut directions, &mut faces);
+        }
+
+        if self.welded {
+            data = data.welded();
+        }
+
+        if clipped || wrapped {
+            data = data.compact();
+        }
+        if self.inverted {
+            data.flip_winding();
+        }
+
+        Ok(data)
+    }
+}
+
+/// a rectangle of the grid of a face
+#[derive(Debug, Clone, Copy)]
+struct GridBlock {
+    face: CubeFace,
+    /// the indices of the first vertex along the outer and inner loop of the face
+    first: [u32; 2],
+    /// the number of vertices along the outer and inner loop, at least `2`
+    size: [u32; 2],
+}
+
+/// the vertex attributes and indices of a triangle list, attributes left
+/// empty are not part of the mesh
+struct MeshData {
+    points: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    tangents: Vec<[f32; 4]>,
+    uvs: Vec<[f32; 2]>,
+    custom: Vec<AttributeColumn>,
+    indices: Vec<u32>,
+}
+
+impl MeshData {
+    /// an empty triangle list with room for some vertices
+    /// ## Arguments
+    /// - `point_count` - the number of vertices to make room for
+    /// - `custom` - the attributes of the hook of the sphere
+    fn with_capacity(point_count: usize, custom: Vec<MeshVertexAttribute>) -> Self {
+        MeshData {
+            points: Vec::with_capacity(point_count),
+            normals: Vec::with_capacity(point_count),
+            tangents: Vec::with_capacity(point_count),
+            uvs: Vec::with_capacity(point_count),
+            custom: custom
+                .into_iter()
+                .map(|attribute| AttributeColumn {
+                    attribute,
+                    values: Vec::with_capacity(point_count),
+                })
+                .collect(),
+            indices: Vec::new(),
+        }
+    }
+
+    /// convert to a bevy mesh, using 16 bit indices when every vertex can be addressed
+    /// ## Arguments
+    /// - `topology` - how the indices are read, triangles or lines
+    /// ## Return
+    /// - the mesh, or the custom attribute whose values do not match its format
+    fn into_mesh(self, topology: PrimitiveTopology) -> Result<Mesh, CubeSphereError> {
+        let indices = if self.points.len() <= u16::MAX as usize + 1 {
+            Indices::U16(self.indices.iter().map(|i| *i as u16).collect())
+        } else {
+            Indices::U32(self.indices)
+        };
+
+        let mut mesh = Mesh::new(topology);
+        mesh.set_indices(Some(indices));
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.points);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        if !self.tangents.is_empty() {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_TANGENT, self.tangents);
+        }
+        if !self.uvs.is_empty() {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs);
+        }
+        for column in self.custom {
+            let attribute = column.attribute.clone();
+            let values = column
+                .into_values()
+                .ok_or(CubeSphereError::InvalidAttribute(attribute.name))?;
+            mesh.insert_attribute(attribute, values);
+        }
+
+        Ok(mesh)
+    }
+
+    /// split into several meshes of consecutive triangles
+    /// ## Arguments
+    /// - `max_vertices` - the maximum number of vertices in each mesh, at least `3`
+    fn split(self, max_vertices: usize) -> Vec<MeshData> {
+        let mut parts = Vec::new();
+        let mut local = vec![u32::MAX; self.points.len()];
+        let mut used: Vec<u32> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+
+        for triangle in self.indices.chunks(3) {
+            let added = triangle
+                .iter()
+                .filter(|i| local[**i as usize] == u32::MAX)
+                .count();
+            if used.len() + added > max_vertices {
+                parts.push(self.gather(&used, std::mem::take(&mut indices)));
+                for i in used.drain(..) {
+                    local[i as usize] = u32::MAX;
+                }
             }
+
+            for i in triangle {
+                if local[*i as usize] == u32::MAX {
+                    local[*i as usize] = used.len() as u32;
+                    used.push(*i);
+                }
+                indices.push(local[*i as usize]);
+            }
+        }
+        if !indices.is_empty() {
+            parts.push(self.gather(&used, indices));
+        }
+
+        parts
+    }
+
+    /// reverse the winding of every triangle
+    fn flip_winding(&mut self) {
+        for triangle in self.indices.chunks_mut(3) {
+            triangle.swap(1, 2);
+        }
+    }
+
+    /// drop the vertices that no triangle refers to, keeping the order of the others
+    fn compact(self) -> MeshData {
+        let mut local = vec![u32::MAX; self.points.len()];
+        for i in &self.indices {
+            local[*i as usize] = 0;
+        }
+        let mut used = Vec::new();
+        for (i, l) in local.iter_mut().enumerate() {
+            if *l == 0 {
+                *l = used.len() as u32;
+                used.push(i as u32);
+            }
+        }
+
+        let indices = self.indices.iter().map(|i| local[*i as usize]).collect();
+        self.gather(&used, indices)
+    }
+
+    /// share the copies of a vertex with the same position, normal and custom
+    /// attributes, dropping the uv coordinates and the tangents
+    fn welded(mut self) -> MeshData {
+        self.uvs.clear();
+        self.tangents.clear();
+        let (remap, welded_count) = self.weld_map();
+
+        let mut first = vec![0; welded_count];
+        for (v, w) in remap.iter().enumerate().rev() {
+            first[*w as usize] = v as u32;
         }
+        let indices = self.indices.iter().map(|i| remap[*i as usize]).collect();
+        self.gather(&first, indices)
+    }
 
-        // construct faces
-        let total_index_count = CUBE_FACE_COUNT * 2 * (sphere.resolution - 1).pow(2);
-        let mut indices: Vec<u32> = Vec::with_capacity(total_index_count);
-        insert_indices(sphere.resolution as u32, &mut indices);
+    /// map every vertex to a vertex shared by the copies with the same
+    /// position, normal and custom attributes
+    /// ## Return
+    /// - the welded index of every vertex
+    /// - the number of welded vertices
+    fn weld_map(&self) -> (Vec<u32>, usize) {
+        let mut welded = HashMap::new();
+        let remap = (0..self.points.len())
+            .map(|v| {
+                let next = welded.len() as u32;
+                let key = (
+                    self.points[v].map(f32::to_bits),
+                    self.normals[v].map(f32::to_bits),
+                    self.custom
+                        .iter()
+                        .map(|column| column.values[v].to_bits())
+                        .collect::<Vec<_>>(),
+                );
+                *welded.entry(key).or_insert(next)
+            })
+            .collect();
 
-        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
-        mesh.set_indices(Some(Indices::U32(indices)));
-        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, points);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_TANGENT, tangents);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        (remap, welded.len())
+    }
+
+    /// copy a subset of the vertices
+    /// ## Arguments
+    /// - `used` - the vertices to keep, in their new order
+    /// - `indices` - the indices into the kept vertices
+    fn gather(&self, used: &[u32], indices: Vec<u32>) -> MeshData {
+        fn pick<T: Copy>(values: &[T], used: &[u32]) -> Vec<T> {
+            if values.is_empty() {
+                Vec::new()
+            } else {
+                used.iter().map(|i| values[*i as usize]).collect()
+            }
+        }
 
-        mesh
+        MeshData {
+            points: pick(&self.points, used),
+            normals: pick(&self.normals, used),
+            tangents: pick(&self.tangents, used),
+            uvs: pick(&self.uvs, used),
+            custom: self
+                .custom
+                .iter()
+                .map(|column| AttributeColumn {
+                    attribute: column.attribute.clone(),
+                    values: pick(&column.values, used),
+                })
+                .collect(),
+            indices,
+        }
     }
 }
 
-/// convert a unit cube vertex to a unit sphere vertex
+/// location of a grid vertex on the cube, scaled so that the grid spacing is `2`
 /// ## Arguments
-/// - `pt` - the location of cube vertex, range of `[-1, 1]`
+/// - `f` - the face of the grid
+/// - `i` - the index along the outer loop of the face
+/// - `j` - the index along the inner loop of the face
+/// - `n` - the number of vertices on the edge of the cube
 /// ## Return
-/// - the location of sphere vertex, range of `[-1, 1]`
-fn unit_cube_point_to_unit_sphere_point(pt: &[f32; 3]) -> [f32; 3] {
-    let x = pt[0];
-    let y = pt[1];
-    let z = pt[2];
+/// - the lattice point, range of `[-(n - 1), n - 1]`, shared by all faces
+///   meeting at that vertex
+fn cube_lattice_point(f: CubeFace, i: u32, j: u32, n: u32) -> [i64; 3] {
+    GridCoord::new(f, i, j).lattice_point(n as i64 - 1)
+}
 
-    let x2 = x.powi(2);
-    let y2 = y.powi(2);
-    let z2 = z.powi(2);
+/// the face a point on the unit cube lies on and its coordinates along that face
+/// ## Arguments
+/// - `pt` - the location of cube vertex on the surface of the cube, range of `[-1, 1]`
+/// ## Return
+/// - the face, the one of the largest coordinate of `pt`
+/// - the coordinates along the outer and inner loop of the face, range of `[-1, 1]`
+fn unit_cube_point_to_face_coords(pt: &[f32; 3]) -> (CubeFace, [f32; 2]) {
+    let abs = pt.map(f32::abs);
+    let axis = if abs[0] >= abs[1] && abs[0] >= abs[2] {
+        0
+    } else if abs[1] >= abs[2] {
+        1
+    } else {
+        2
+    };
 
-    let nx = x * (1. - y2 / 2. - z2 / 2. + y2 * z2 / 3.).sqrt();
-    let ny = y * (1. - x2 / 2. - z2 / 2. + x2 * z2 / 3.).sqrt();
-    let nz = z * (1. - x2 / 2. - y2 / 2. + x2 * y2 / 3.).sqrt();
+    let mut normal = [0; 3];
+    normal[axis] = if pt[axis] > 0. { 1 } else { -1 };
+    let face = CubeFace::from_normal(normal);
 
-    return [nx, ny, nz];
+    let [u, v] = face.axes();
+    (face, [dot(pt, &u), dot(pt, &v)])
+}
+
+/// the location on the unit cube of face coordinates
+/// ## Arguments
+/// - `f` - the face
+/// - `coords` - the coordinates along the outer and inner loop of the face, range of `[-1, 1]`
+/// ## Return
+/// - the location of cube vertex, range of `[-1, 1]`
+pub(crate) fn face_coords_to_unit_cube_point(f: CubeFace, coords: [f32; 2]) -> [f32; 3] {
+    let normal = f.normal();
+    let [u, v] = f.axes();
+    [0, 1, 2].map(|k| normal[k] + coords[0] * u[k] + coords[1] * v[k])
+}
+
+/// the face and face coordinates of a direction, inverting a projection
+/// ## Arguments
+/// - `dir` - the direction of the point from the center of the sphere, any length
+/// - `projection` - the projection from the cube to the sphere
+pub(crate) fn sphere_point_to_face_coords(
+    dir: &[f32; 3],
+    projection: &dyn CubeProjection,
+) -> (CubeFace, [f32; 2]) {
+    let unit_sphere_point = normalize(dir);
+    let unit_cube_point = projection.sphere_to_cube(&unit_sphere_point);
+    unit_cube_point_to_face_coords(&unit_cube_point)
+}
+
+/// location of a grid vertex on the unit cube
+/// ## Arguments
+/// - `f` - the face of the grid
+/// - `i` - the index along the outer loop of the face
+/// - `j` - the index along the inner loop of the face
+/// - `n` - the number of vertices on the edge of the cube
+/// ## Return
+/// - the location of cube vertex, range of `[-1, 1]`, bit-identical for all
+///   faces meeting at that vertex
+fn unit_cube_grid_point(f: CubeFace, i: u32, j: u32, n: u32) -> [f32; 3] {
+    let scale = (n - 1) as f32;
+    cube_lattice_point(f, i, j, n).map(|c| c as f32 / scale)
 }
 
 /// convert a unit sphere vertex to a sphere vertex based on radius
@@ -204,7 +928,7 @@ fn unit_cube_point_to_unit_sphere_point(pt: &[f32; 3]) -> [f32; 3] {
 /// ## Return
 /// - the location of sphere vertex, range of `[-r, r]`
 fn unit_sphere_point_to_sphere_point(pt: &[f32; 3], r: f32) -> [f32; 3] {
-    return pt.map(|e| e * r);
+    pt.map(|e| e * r)
 }
 
 /// compute normal vector for a unit sphere vertex
@@ -213,236 +937,444 @@ fn unit_sphere_point_to_sphere_point(pt: &[f32; 3], r: f32) -> [f32; 3] {
 /// ## Return
 /// - the normal vector on that specific vertex
 fn unit_sphere_point_to_normal(pt: &[f32; 3]) -> [f32; 3] {
-    return *pt;
+    *pt
 }
 
-/// compute tangent vector for a unit sphere vertex
+/// compute the normal of an ellipsoid at the image of a unit sphere vertex,
+/// the gradient of `(x / a)² + (y / b)² + (z / c)²`
 /// ## Arguments
 /// - `pt` - the location of sphere vertex, range of `[-1, 1]`
-/// - `f` - the face that this vertex belongs to in its original cube
+/// - `radii` - the radii of the ellipsoid along the axes
 /// ## Return
-/// - the tangent vector on that specific vertex
-fn unit_sphere_point_to_tangent(pt: &[f32; 3], f: CubeFace) -> [f32; 4] {
-    let normal = unit_sphere_point_to_normal(pt);
-    let other = match f {
-        CubeFace::Front | CubeFace::Back | CubeFace::Left | CubeFace::Right => {
-            [0_f32, 1_f32, 0_f32]
-        }
-        CubeFace::Top | CubeFace::Bottom => [0_f32, 0_f32, 1_f32],
-    };
+/// - the normal vector on that specific vertex, unit length
+fn unit_sphere_point_to_ellipsoid_normal(pt: &[f32; 3], radii: &[f32; 3]) -> [f32; 3] {
+    normalize(&[0, 1, 2].map(|k| pt[k] / radii[k]))
+}
 
-    let x = normal[0];
-    let y = normal[1];
-    let z = normal[2];
+/// carry a tangent of the unit sphere over to an ellipsoid, the ellipsoid
+/// being the sphere scaled per axis, its tangents are scaled the same way and
+/// keep their handedness
+/// ## Arguments
+/// - `tangent` - the tangent on the unit sphere
+/// - `radii` - the radii of the ellipsoid along the axes
+fn sphere_tangent_to_ellipsoid_tangent(tangent: &[f32; 4], radii: &[f32; 3]) -> [f32; 4] {
+    let scaled = [0, 1, 2].map(|k| tangent[k] * radii[k]);
+    let norm = length(&scaled);
+    [
+        scaled[0] / norm,
+        scaled[1] / norm,
+        scaled[2] / norm,
+        tangent[3],
+    ]
+}
 
-    let u = other[0];
-    let v = other[1];
-    let w = other[2];
+/// the distance along the face coordinates between the samples of the uv
+/// layout of a tangent
+const TANGENT_STEP: f32 = 4e-3;
 
-    let a = w * y - v * z;
-    let b = u * z - w * x;
-    let c = v * x - u * y;
-    let norm = (a.powi(2) + b.powi(2) + c.powi(2)).sqrt();
+/// compute the tangent vector of a vertex, pointing where the uv coordinate
+/// `u` grows along the surface as MikkTSpace expects, from the differences of
+/// the uv layout of the sphere around the vertex
+/// ## Arguments
+/// - `vertex` - the location of the vertex on the cube and on the unit sphere, and its face
+/// - `sphere` - the sphere being generated, for its projection and uv layout
+/// ## Return
+/// - the tangent vector of the unit sphere, `w` is `-1` where the uv is
+///   mirrored seen from the side the mesh faces
+fn vertex_tangent(vertex: &VertexInfo, sphere: &CubeSphere) -> [f32; 4] {
+    let face = vertex.face;
+    let normal = face.normal();
+    let [axis_u, axis_v] = face.axes();
 
-    [a / norm, b / norm, c / norm, 1.]
-}
+    // the face is extended past its borders by the projection, a point of the
+    // disc of a face that lies on another face by the straight projection. The
+    // inverse projection may put a point of a border on the neighboring face
+    let on_face = dot(&vertex.cube_point, &normal) >= 1. - 1e-4;
+    let coords = if on_face {
+        [&axis_u, &axis_v].map(|axis| dot(&vertex.cube_point, axis))
+    } else {
+        let height = dot(&vertex.sphere_point, &normal);
+        [&axis_u, &axis_v].map(|axis| dot(&vertex.sphere_point, axis) / height)
+    };
+    let sample = |coords: [f32; 2]| {
+        let plane_point = face_coords_to_unit_cube_point(face, coords);
+        let (cube_point, sphere_point) = if on_face {
+            (plane_point, sphere.projection.cube_to_sphere(&plane_point))
+        } else {
+            let sphere_point = normalize(&plane_point);
+            (
+                sphere.projection.sphere_to_cube(&sphere_point),
+                sphere_point,
+            )
+        };
+        (
+            sphere_point,
+            sphere.vertex_uv(&cube_point, &sphere_point, face),
+        )
+    };
+    let difference = |k: usize| {
+        let (mut low, mut high) = (coords, coords);
+        low[k] -= TANGENT_STEP;
+        high[k] += TANGENT_STEP;
+        let ((p0, uv0), (p1, uv1)) = (sample(low), sample(high));
+        let mut du = uv1[0] - uv0[0];
+        if sphere.uv_layout.antimeridian() {
+            // the samples of a vertex on the antimeridian are on both sides
+            du -= du.round();
+        }
+        ([0, 1, 2].map(|c| p1[c] - p0[c]), [du, uv1[1] - uv0[1]])
+    };
+    let ((e1, d1), (e2, d2)) = (difference(0), difference(1));
+
+    // the derivatives of the point along `u` and `v`
+    let det = d1[0] * d2[1] - d2[0] * d1[1];
+    let tangent = [0, 1, 2].map(|c| (e1[c] * d2[1] - e2[c] * d1[1]) / det);
+    let bitangent = [0, 1, 2].map(|c| (e2[c] * d1[0] - e1[c] * d2[0]) / det);
+
+    let norm = length(&vertex.sphere_point);
+    let side = if sphere.inverted { -1. } else { 1. };
+    let normal = unit_sphere_point_to_normal(&vertex.sphere_point).map(|c| side * c / norm);
+    let along = dot(&tangent, &normal);
+    let tangent = [0, 1, 2].map(|c| tangent[c] - along * normal[c]);
+    let norm = length(&tangent);
+    let w = if dot(&cross(&normal, &tangent), &bitangent) < 0. {
+        -1.
+    } else {
+        1.
+    };
 
-const UV_SPHERE_RADIUS: f32 = 0.1876806;
-const UV_COORDINATE_0: [f32; 2] = [0.0000000, 0.0000000];
-const UV_COORDINATE_1: [f32; 2] = [0.6246388, 0.0000000];
-const UV_COORDINATE_2: [f32; 2] = [0.3123194, 0.2082129];
-const UV_COORDINATE_3: [f32; 2] = [0.0000000, 0.4164259];
-const UV_COORDINATE_4: [f32; 2] = [0.6246388, 0.4164259];
-const UV_COORDINATE_5: [f32; 2] = [0.3123194, 0.6246388];
+    [tangent[0] / norm, tangent[1] / norm, tangent[2] / norm, w]
+}
 
-/// face and its corresponding uv coordinate on texture
+/// mirror a point across the plane of the normal and the second axis of a
+/// face, which mirrors `u` on that face
 /// ## Arguments
+/// - `pt` - the location of the point on the cube or on the sphere
 /// - `f` - the face
-/// ## Return
-/// - the uv coordinate of this specific face
-fn face_to_uv_coordinate(f: CubeFace) -> [f32; 2] {
-    match f {
-        CubeFace::Front => UV_COORDINATE_0,
-        CubeFace::Back => UV_COORDINATE_3,
-        CubeFace::Left => UV_COORDINATE_2,
-        CubeFace::Right => UV_COORDINATE_5,
-        CubeFace::Top => UV_COORDINATE_1,
-        CubeFace::Bottom => UV_COORDINATE_4,
-    }
+pub(crate) fn mirror_along_u(pt: &[f32; 3], f: CubeFace) -> [f32; 3] {
+    let [u, _] = f.axes();
+    let along = dot(pt, &u);
+    [0, 1, 2].map(|k| pt[k] - 2. * along * u[k])
 }
 
-/// compute uv coordinate for a unit sphere vertex
+/// compute and append the vertex attributes of a cube vertex
 /// ## Arguments
-/// - `pt` - the location of sphere vertex, range of `[-1, 1]`
-/// - `f` - the face that this vertex belongs to in its original cube
-/// ## Return
-/// - the uv coordinate on that specific vertex
-fn unit_sphere_point_to_uv(pt: &[f32; 3], f: CubeFace) -> [f32; 2] {
-    let x = pt[0];
-    let y = pt[1];
-    let z = pt[2];
-
-    fn scale(i: f32) -> f32 {
-        (i + 1.) * UV_SPHERE_RADIUS
+/// - `vertex` - the location of the vertex on the cube and on the unit sphere, and its face
+/// - `sphere` - the sphere being generated
+/// - `step` - the angle between the samples of the displaced normal, about half the grid spacing
+/// - `data` - the attributes to append to
+fn insert_attributes(vertex: &VertexInfo, sphere: &CubeSphere, step: f32, data: &mut MeshData) {
+    let unit_sphere_point = &vertex.sphere_point;
+    let face = vertex.face;
+    let (mut normal, mut tangent) = sphere.surface_frame(vertex);
+    if sphere.elevation.is_some() {
+        normal = elevation::surface_normal(|d| sphere.surface_point(d), unit_sphere_point, step);
+        tangent = elevation::orthogonalize(&tangent, &normal);
+        if sphere.inverted {
+            normal = normal.map(|c| -c);
+        }
     }
+    let uv = sphere.vertex_uv(&vertex.cube_point, unit_sphere_point, face);
+
+    data.points.push(sphere.surface_point(unit_sphere_point));
+    data.normals.push(normal);
+    data.tangents.push(tangent);
+    data.uvs.push(uv);
 
-    fn flip(i: f32) -> f32 {
-        UV_SPHERE_RADIUS * 2. - i
+    if data.custom.is_empty() {
+        return;
     }
+    let mut values = Vec::with_capacity(data.custom.len());
+    if sphere.cube_attributes || sphere.uv_layout.face_attribute() {
+        values.push(AttributeValue::Uint32(face.index() as u32));
+    }
+    if sphere.cube_attributes {
+        values.push(AttributeValue::Float32x3(vertex.cube_point));
+    }
+    if let Some(hook) = &sphere.attribute_hook {
+        let first = values.len();
+        values.extend(data.custom[first..].iter().map(|c| {
+            AttributeValue::zero(c.attribute.format).unwrap_or(AttributeValue::Float32(0.))
+        }));
+        hook.compute(vertex, &mut values[first..]);
+    }
+    for (column, value) in data.custom.iter_mut().zip(values) {
+        column.values.push(value);
+    }
+}
 
-    let coord = face_to_uv_coordinate(f);
+/// triangulate a grid of `n × n` vertices emitted with the outer loop first,
+/// skipping every other vertex along some of its edges
+/// ## Arguments
+/// - `n` - the number of vertices on the edge of the grid, odd
+/// - `mask` - the edges to stitch, bit `k` for the edge `FaceEdge::ALL[k]`
+/// - `indices` - the indices to append the triangles to
+fn insert_stitched_grid_indices(n: u32, mask: u8, indices: &mut Vec<u32>) {
+    let stitched = |edge: FaceEdge| mask & (1 << edge.index()) != 0;
+    let last = n - 1;
 
-    match f {
-        CubeFace::Front => [coord[0] + scale(x), 1. - (coord[1] + scale(y))],
-        CubeFace::Back => [coord[0] + flip(scale(x)), 1. - (coord[1] + scale(y))],
-        CubeFace::Left => [coord[0] + scale(z), 1. - (coord[1] + scale(y))],
-        CubeFace::Right => [coord[0] + flip(scale(z)), 1. - (coord[1] + scale(y))],
-        CubeFace::Top => [coord[0] + scale(x), 1. - (coord[1] + flip(scale(z)))],
-        CubeFace::Bottom => [coord[0] + scale(x), 1. - (coord[1] + scale(z))],
+    // the odd vertices of a stitched edge collapse onto the previous vertex,
+    // which only folds the triangles along that edge onto their neighbors
+    let collapse = |index: u32| {
+        let (mut i, mut j) = (index / n, index % n);
+        if (i == 0 && stitched(FaceEdge::MinU)) || (i == last && stitched(FaceEdge::MaxU)) {
+            j -= j & 1;
+        }
+        if (j == 0 && stitched(FaceEdge::MinV)) || (j == last && stitched(FaceEdge::MaxV)) {
+            i -= i & 1;
+        }
+        i * n + j
+    };
+
+    let mut grid = Vec::with_capacity(6 * last.pow(2) as usize);
+    insert_grid_indices(n, 0, &mut grid);
+    for triangle in grid.chunks(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(collapse);
+        if a != b && b != c && c != a {
+            indices.extend([a, b, c]);
+        }
     }
 }
 
-fn insert_attributes(
-    unit_cube_point: &[f32; 3],
-    radius: f32,
-    face: CubeFace,
-    points: &mut Vec<[f32; 3]>,
-    normals: &mut Vec<[f32; 3]>,
-    tangents: &mut Vec<[f32; 4]>,
-    uvs: &mut Vec<[f32; 2]>,
-) {
-    let unit_sphere_point = unit_cube_point_to_unit_sphere_point(unit_cube_point);
-    points.push(unit_sphere_point_to_sphere_point(
-        &unit_sphere_point,
-        radius,
-    ));
-    normals.push(unit_sphere_point_to_normal(&unit_sphere_point));
-    tangents.push(unit_sphere_point_to_tangent(&unit_sphere_point, face));
-    uvs.push(unit_sphere_point_to_uv(&unit_sphere_point, face))
+/// triangulate a grid of `n × n` vertices emitted with the outer loop first
+/// ## Arguments
+/// - `n` - the number of vertices on the edge of the grid
+/// - `offset` - the index of the first vertex of the grid
+/// - `indices` - the indices to append the triangles to
+fn insert_grid_indices(n: u32, offset: u32, indices: &mut Vec<u32>) {
+    insert_block_indices(n, n, offset, indices);
 }
 
-fn insert_indices(n: u32, indices: &mut Vec<u32>) {
-    let edge_count = n - 1;
-    let face_count = CUBE_FACE_COUNT as u32;
-
-    for f in 0..face_count as u32 {
-        for x in 0..edge_count {
-            for y in 0..edge_count {
-                let i = f * n.pow(2) + y * n + x;
+/// triangulate a grid of `rows × columns` vertices emitted with the outer loop first
+/// ## Arguments
+/// - `rows` - the number of vertices along the outer loop
+/// - `columns` - the number of vertices along the inner loop
+/// - `offset` - the index of the first vertex of the grid
+/// - `indices` - the indices to append the triangles to
+fn insert_block_indices(rows: u32, columns: u32, offset: u32, indices: &mut Vec<u32>) {
+    for x in 0..columns - 1 {
+        for y in 0..rows - 1 {
+            let i = offset + y * columns + x;
 
-                // counter-clockwise = front
-                indices.push(i);
-                indices.push(i + n);
-                indices.push(i + 1);
+            // counter-clockwise = front
+            indices.push(i);
+            indices.push(i + columns);
+            indices.push(i + 1);
 
-                indices.push(i + 1);
-                indices.push(i + n);
-                indices.push(i + n + 1);
-            }
+            indices.push(i + 1);
+            indices.push(i + columns);
+            indices.push(i + columns + 1);
         }
     }
 }
 
 impl CubeSphere {
-    /// compute uv coordinate for a unit sphere vertex
+    /// the point of the surface in a direction, displaced by the elevation
     /// ## Arguments
-    /// - `pt` - the location of sphere vertex, range of `[-1, 1]`
-    /// ## Return
-    /// - all possible uv coordinates for that specific vertex, please assign
-    ///   the same value for these coordinates in the texture
-    pub fn point_to_uvs(pt: &[f32; 3]) -> [[f32; 2]; 3] {
-        let x = pt[0];
-        let y = pt[1];
-        let z = pt[2];
-
-        let face_x = if x > 0. {
-            unit_sphere_point_to_uv(pt, CubeFace::Right)
-        } else {
-            unit_sphere_point_to_uv(pt, CubeFace::Left)
+    /// - `unit_sphere_point` - the direction, unit length
+    pub(crate) fn surface_point(&self, unit_sphere_point: &[f32; 3]) -> [f32; 3] {
+        let height = match &self.elevation {
+            None => 0.,
+            Some(elevation) => elevation.height(unit_sphere_point),
         };
 
-        let face_y = if y > 0. {
-            unit_sphere_point_to_uv(pt, CubeFace::Top)
-        } else {
-            unit_sphere_point_to_uv(pt, CubeFace::Bottom)
+        match self.radii {
+            None => unit_sphere_point_to_sphere_point(unit_sphere_point, self.radius + height),
+            Some(radii) => {
+                // the height is measured along the normal of the ellipsoid
+                let normal = unit_sphere_point_to_ellipsoid_normal(unit_sphere_point, &radii);
+                [0, 1, 2].map(|k| unit_sphere_point[k] * radii[k] + height * normal[k])
+            }
+        }
+    }
+
+    /// map the vertices of a mesh generated without `welded` to the vertices
+    /// of the mesh generated with `welded`, before clipping
+    /// ## Return
+    /// - for every per-face vertex, the index of the shared vertex it
+    ///   collapses to, or the reason the mesh cannot be generated
+    pub fn weld_map(&self) -> Result<Vec<u32>, CubeSphereError> {
+        let sphere = CubeSphere {
+            welded: false,
+            ..self.clone()
         };
+        Ok(sphere
+            .build_mesh_data(false, &sphere.face_blocks())?
+            .weld_map()
+            .0)
+    }
+
+    /// find the cube face a point on the sphere belongs to, inverting the projection
+    /// ## Arguments
+    /// - `dir` - the direction of the point from the center of the sphere, any length
+    /// ## Return
+    /// - the face of the point, `None` for a zero or non-finite direction
+    /// - the coordinates along the outer and inner loop of that face, range of
+    ///   `[-1, 1]`, the grid vertex `(i, j)` sits at `2 * (i, j) / (resolution - 1) - 1`
+    pub fn sphere_to_face_coords(&self, dir: &[f32; 3]) -> Option<(CubeFace, [f32; 2])> {
+        let norm = length(dir);
+        if norm == 0. || !norm.is_finite() {
+            return None;
+        }
+        Some(sphere_point_to_face_coords(dir, self.projection.as_ref()))
+    }
 
-        let face_z = if z > 0. {
-            unit_sphere_point_to_uv(pt, CubeFace::Front)
+    /// find the point on the unit sphere of face coordinates, the inverse of
+    /// [`CubeSphere::sphere_to_face_coords`]
+    /// ## Arguments
+    /// - `face` - the face
+    /// - `coords` - the coordinates along the outer and inner loop of the face, range of `[-1, 1]`
+    /// ## Return
+    /// - the unit sphere point, the grid vertex `(i, j)` for the coordinates
+    ///   `2 * (i, j) / (resolution - 1) - 1`
+    pub fn face_coords_to_sphere(&self, face: CubeFace, coords: [f32; 2]) -> [f32; 3] {
+        let cube_point = face_coords_to_unit_cube_point(face, coords);
+        self.projection.cube_to_sphere(&cube_point)
+    }
+
+    /// the normal and the tangent of the surface at a vertex, before the elevation
+    /// ## Arguments
+    /// - `vertex` - the location of the vertex on the cube and on the unit sphere, and its face
+    /// ## Return
+    /// - the normal vector, pointing to the center when `inverted`
+    /// - the tangent vector
+    fn surface_frame(&self, vertex: &VertexInfo) -> ([f32; 3], [f32; 4]) {
+        let mut tangent = vertex_tangent(vertex, self);
+        let mut normal = unit_sphere_point_to_normal(&vertex.sphere_point);
+        if let Some(radii) = self.radii {
+            tangent = sphere_tangent_to_ellipsoid_tangent(&tangent, &radii);
+            normal = unit_sphere_point_to_ellipsoid_normal(&vertex.sphere_point, &radii);
+        }
+        if self.inverted {
+            normal = normal.map(|c| -c);
+        }
+        (normal, tangent)
+    }
+
+    /// compute the uv coordinate of a vertex in the layout of the sphere,
+    /// mirrored along `u` within every face when `inverted` so that textures
+    /// read the right way from the inside
+    /// ## Arguments
+    /// - `cube_point` - the location of the vertex on the cube, range of `[-1, 1]`
+    /// - `sphere_point` - the location of the vertex on the unit sphere
+    /// - `face` - the face that this vertex belongs to
+    fn vertex_uv(
+        &self,
+        cube_point: &[f32; 3],
+        sphere_point: &[f32; 3],
+        face: CubeFace,
+    ) -> [f32; 2] {
+        if self.inverted {
+            let cube_point = self.uv_layout.mirror(cube_point, face);
+            let sphere_point = self.uv_layout.mirror(sphere_point, face);
+            let face = self.mirrored_face(face);
+            self.uv_layout.point_to_uv(&cube_point, &sphere_point, face)
         } else {
-            unit_sphere_point_to_uv(pt, CubeFace::Back)
-        };
+            self.uv_layout.point_to_uv(cube_point, sphere_point, face)
+        }
+    }
 
-        [face_x, face_y, face_z]
+    /// the face a face lands on when mirrored by the uv layout, itself unless
+    /// the layout mirrors the whole sphere at once
+    fn mirrored_face(&self, face: CubeFace) -> CubeFace {
+        let normal = self.uv_layout.mirror(&face.normal(), face);
+        CubeFace::from_normal(normal.map(|c| c.round() as i32))
     }
 
-    /// convert uv coordinate to corresponding face
+    /// compute uv coordinate for a unit sphere vertex
     /// ## Arguments
-    /// - `uv` - the uv coordinate
+    /// - `pt` - the location of sphere vertex, range of `[-1, 1]`
     /// ## Return
-    /// - the corresponding face
-    fn uv_to_face(uv: &[f32; 2]) -> CubeFace {
-        [
-            CubeFace::Front,
-            CubeFace::Back,
-            CubeFace::Left,
-            CubeFace::Right,
-            CubeFace::Top,
-            CubeFace::Bottom,
-        ]
-        .into_iter()
-        .min_by_key(|f| {
-            let u = uv[0];
-            let v = uv[1];
-
-            let x = face_to_uv_coordinate(*f)[0] + UV_SPHERE_RADIUS;
-            let y = face_to_uv_coordinate(*f)[1] + UV_SPHERE_RADIUS;
-
-            FloatOrd((x - u).powi(2) + (v - y).powi(2))
-        })
-        .unwrap()
+    /// - all possible uv coordinates for that specific vertex, one per face
+    ///   of the layout showing it, please assign the same value for these
+    ///   coordinates in the texture
+    pub fn point_to_uvs(&self, pt: &[f32; 3]) -> Vec<[f32; 2]> {
+        let cube_point = self.projection.sphere_to_cube(pt);
+        self.uv_layout
+            .point_faces(&cube_point, pt)
+            .into_iter()
+            .map(|face| self.vertex_uv(&cube_point, pt, face))
+            .collect()
     }
 
-    /// compute the unit sphere vertex based on uv coordinate
+    /// compute the face and the unit sphere vertex based on uv coordinate
     /// ## Arguments
     /// - `uv` - the uv coordinate, range of `[0, 1]`
-    /// ## Return
-    /// - the unit sphere vertex, `None` means that uv coordinate map to nowhere on the surface of the sphere
-    pub fn uv_to_point(uv: &[f32; 2]) -> Option<[f32; 3]> {
-        let face = CubeSphere::uv_to_face(uv);
-        let coord = face_to_uv_coordinate(face);
-        let u = uv[0] - coord[0] - UV_SPHERE_RADIUS;
-        let v = uv[1] - coord[1] - UV_SPHERE_RADIUS;
-
-        let w2 = UV_SPHERE_RADIUS.powi(2) - u.powi(2) - v.powi(2);
-        if w2 < 0. {
-            None
+    fn uv_to_vertex(&self, uv: &[f32; 2]) -> Option<VertexInfo> {
+        let (face, pt) = self.uv_layout.uv_to_point(uv, self.projection.as_ref())?;
+        Some(self.layout_vertex(&pt, face))
+    }
+
+    /// the vertex of the mesh whose uv is the one of a point in the layout
+    /// ## Arguments
+    /// - `pt` - the unit sphere vertex given by the layout
+    /// - `face` - the face whose part of the texture shows the point
+    fn layout_vertex(&self, pt: &[f32; 3], face: CubeFace) -> VertexInfo {
+        let (sphere_point, face) = if self.inverted {
+            (self.uv_layout.mirror(pt, face), self.mirrored_face(face))
         } else {
-            let w = w2.sqrt();
-            Some(match face {
-                CubeFace::Front | CubeFace::Back => [u, v, w],
-                CubeFace::Left | CubeFace::Right => [w, v, u],
-                CubeFace::Top | CubeFace::Bottom => [u, w, v],
-            })
+            (*pt, face)
+        };
+        VertexInfo {
+            cube_point: self.projection.sphere_to_cube(&sphere_point),
+            sphere_point,
+            face,
+            grid: None,
         }
     }
 
-    /// compute the normal vector vertex based on uv coordinate
+    /// compute the unit sphere vertex based on uv coordinate, the inverse of
+    /// [`CubeSphere::point_to_uvs`]
+    /// ## Arguments
+    /// - `uv` - the uv coordinate, range of `[0, 1]`
+    /// ## Return
+    /// - the unit sphere vertex, `None` means that uv coordinate map to nowhere on the surface of the sphere,
+    ///   or that the layout needs the face, e.g. [`uv::FaceLayers`], see [`CubeSphere::face_uv_to_point`]
+    pub fn uv_to_point(&self, uv: &[f32; 2]) -> Option<[f32; 3]> {
+        self.uv_to_vertex(uv).map(|vertex| vertex.sphere_point)
+    }
+
+    /// compute the unit sphere vertex based on uv coordinate within the part
+    /// of the texture of a face, e.g. a layer of [`uv::FaceLayers`]
+    /// ## Arguments
+    /// - `face` - the face whose part of the texture holds the coordinate
+    /// - `uv` - the uv coordinate, range of `[0, 1]`
+    /// ## Return
+    /// - the unit sphere vertex, `None` means that uv coordinate map to nowhere on that face
+    pub fn face_uv_to_point(&self, face: CubeFace, uv: &[f32; 2]) -> Option<[f32; 3]> {
+        self.uv_layout
+            .face_uv_to_point(uv, face, self.projection.as_ref())
+            .map(|pt| self.layout_vertex(&pt, face).sphere_point)
+    }
+
+    /// compute the normal vector vertex based on uv coordinate, of the
+    /// surface before the elevation
     /// ## Arguments
     /// - `uv` - the uv coordinate, range of `[0, 1]`
     /// ## Return
     /// - the normal vector, `None` means that uv coordinate map to nowhere on the surface of the sphere
-    pub fn uv_to_normal(uv: &[f32; 2]) -> Option<[f32; 3]> {
-        CubeSphere::uv_to_point(uv).map(|pt| unit_sphere_point_to_normal(&pt))
+    pub fn uv_to_normal(&self, uv: &[f32; 2]) -> Option<[f32; 3]> {
+        self.uv_to_vertex(uv).map(|vertex| {
+            let normal = match self.radii {
+                None => unit_sphere_point_to_normal(&vertex.sphere_point),
+                Some(radii) => unit_sphere_point_to_ellipsoid_normal(&vertex.sphere_point, &radii),
+            };
+            if self.inverted {
+                normal.map(|c| -c)
+            } else {
+                normal
+            }
+        })
     }
 
-    /// compute the tangent vector vertex based on uv coordinate
+    /// compute the tangent vector vertex based on uv coordinate, of the
+    /// surface before the elevation
     /// ## Arguments
     /// - `uv` - the uv coordinate, range of `[0, 1]`
     /// ## Return
     /// - the tangent vector, `None` means that uv coordinate map to nowhere on the surface of the sphere
-    pub fn uv_to_tangent(uv: &[f32; 2]) -> Option<[f32; 4]> {
-        let face = CubeSphere::uv_to_face(uv);
-        CubeSphere::uv_to_point(uv).map(|pt| unit_sphere_point_to_tangent(&pt, face))
+    pub fn uv_to_tangent(&self, uv: &[f32; 2]) -> Option<[f32; 4]> {
+        self.uv_to_vertex(uv)
+            .map(|vertex| self.surface_frame(&vertex).1)
     }
 }
@@ -0,0 +1,332 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bevy::prelude::Mesh;
+
+use crate::vector::distance;
+use crate::{
+    face_coords_to_unit_cube_point, CellId, CubeFace, CubeSphere, CubeSphereError, FaceEdge,
+    PatchSeam,
+};
+
+/// how finely the sphere is split into patches around a viewpoint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LodSettings {
+    /// the deepest level of the quadtrees, at most [`CellId::MAX_LEVEL`]
+    pub max_level: u8,
+    /// the number of vertices on the edge of every patch
+    pub patch_resolution: usize,
+    /// the largest projected distance between two neighboring vertices of a
+    /// patch before it is split, in pixels for a [`Viewpoint::perspective`]
+    pub max_error: f32,
+    /// how the patches hide the cracks along the borders with coarser patches
+    pub seams: LodSeams,
+}
+
+/// how the patches of a [`LodQuadtree`] hide the cracks along the borders with coarser patches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LodSeams {
+    /// leave the cracks open
+    None,
+    /// drop every other vertex along the edges bordering a coarser patch, see
+    /// [`LodQuadtree::stitch_mask`], the patch resolution must be odd
+    Stitch,
+    /// hang a strip of triangles below every edge of every patch, down to this
+    /// distance towards the center of the sphere
+    Skirt(f32),
+}
+
+impl LodSettings {
+    /// the seam of a patch
+    /// ## Arguments
+    /// - `stitch_mask` - the edges bordering a coarser patch, see [`LodQuadtree::stitch_mask`]
+    fn patch_seam(&self, stitch_mask: u8) -> PatchSeam {
+        match self.seams {
+            LodSeams::None => PatchSeam::None,
+            LodSeams::Stitch => PatchSeam::Stitch(stitch_mask),
+            LodSeams::Skirt(depth) => PatchSeam::Skirt(depth),
+        }
+    }
+}
+
+impl Default for LodSettings {
+    fn default() -> Self {
+        LodSettings {
+            max_level: 16,
+            patch_resolution: 17,
+            max_error: 8.,
+            seams: LodSeams::Stitch,
+        }
+    }
+}
+
+/// where the sphere is seen from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewpoint {
+    /// the position of the viewer, relative to the center of the sphere
+    pub position: [f32; 3],
+    /// the projected size of a unit length seen at a unit distance
+    pub projection_scale: f32,
+}
+
+impl Viewpoint {
+    /// a viewpoint measuring the error as the angle under which it is seen, in radians
+    /// ## Arguments
+    /// - `position` - the position of the viewer, relative to the center of the sphere
+    pub fn new(position: [f32; 3]) -> Self {
+        Viewpoint {
+            position,
+            projection_scale: 1.,
+        }
+    }
+
+    /// a viewpoint measuring the error in pixels on the screen of a perspective camera
+    /// ## Arguments
+    /// - `position` - the position of the camera, relative to the center of the sphere
+    /// - `fov_y` - the vertical field of view of the camera, in radians
+    /// - `viewport_height` - the height of the viewport, in pixels
+    pub fn perspective(position: [f32; 3], fov_y: f32, viewport_height: f32) -> Self {
+        Viewpoint {
+            position,
+            projection_scale: viewport_height / (2. * (fov_y / 2.).tan()),
+        }
+    }
+}
+
+/// the patches entering and leaving the selection after [`LodQuadtree::update`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LodUpdate {
+    /// the patches to generate, in increasing order
+    pub added: Vec<CellId>,
+    /// the patches to drop, in increasing order
+    pub removed: Vec<CellId>,
+    /// the patches kept whose stitch mask changed, to generate again, in
+    /// increasing order, always empty unless the seams are stitched
+    pub restitched: Vec<CellId>,
+}
+
+impl LodUpdate {
+    /// whether the selection is unchanged
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.restitched.is_empty()
+    }
+}
+
+/// a quadtree of patches over every face of a cube sphere, split around a
+/// viewpoint so that the patches look equally detailed on screen
+///
+/// the selection covers the whole sphere without overlap, and patches
+/// sharing an edge differ by at most one level, every selected patch is
+/// meshed on its own with [`CubeSphere::try_patch_mesh`]
+#[derive(Debug, Clone)]
+pub struct LodQuadtree {
+    sphere: CubeSphere,
+    settings: LodSettings,
+    /// the selected patches and their stitch masks
+    chunks: BTreeMap<CellId, u8>,
+}
+
+impl LodQuadtree {
+    /// create a quadtree with an empty selection, call [`LodQuadtree::update`] to fill it
+    /// ## Arguments
+    /// - `sphere` - the sphere to split, its `resolution` and `welded` are not used
+    /// - `settings` - how finely to split it
+    /// ## Return
+    /// - the quadtree, or the reason the patches cannot be generated
+    pub fn new(sphere: CubeSphere, settings: LodSettings) -> Result<Self, CubeSphereError> {
+        if settings.max_level > CellId::MAX_LEVEL {
+            return Err(CubeSphereError::InvalidLevel(settings.max_level));
+        }
+        sphere.validate_patch(settings.patch_resolution, settings.patch_seam(0))?;
+
+        Ok(LodQuadtree {
+            sphere,
+            settings,
+            chunks: BTreeMap::new(),
+        })
+    }
+
+    /// the sphere the patches are generated from
+    pub fn sphere(&self) -> &CubeSphere {
+        &self.sphere
+    }
+
+    /// how finely the sphere is split
+    pub fn settings(&self) -> &LodSettings {
+        &self.settings
+    }
+
+    /// the patches selected by the last [`LodQuadtree::update`], in increasing order
+    pub fn chunks(&self) -> impl Iterator<Item = CellId> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// the edges of a selected patch bordering a coarser patch
+    /// ## Arguments
+    /// - `cell` - the patch
+    /// ## Return
+    /// - bit `k` is set when the edge [`FaceEdge::ALL`]`[k]` borders a patch
+    ///   of the level above, `None` if the patch is not selected
+    pub fn stitch_mask(&self, cell: CellId) -> Option<u8> {
+        self.chunks.get(&cell).copied()
+    }
+
+    /// select the patches for a viewpoint without changing the quadtree
+    /// ## Arguments
+    /// - `viewpoint` - where the sphere is seen from
+    /// ## Return
+    /// - the patches covering the sphere, in increasing order
+    pub fn select(&self, viewpoint: &Viewpoint) -> Vec<CellId> {
+        let mut selected = Vec::new();
+        for face in CubeFace::ALL {
+            self.select_cell(CellId::from_face(face), viewpoint, &mut selected);
+        }
+
+        let mut selected: BTreeSet<CellId> = selected.into_iter().collect();
+        balance(&mut selected);
+        selected.into_iter().collect()
+    }
+
+    /// select the patches for a viewpoint
+    /// ## Arguments
+    /// - `viewpoint` - where the sphere is seen from
+    /// ## Return
+    /// - the difference with the previous selection
+    pub fn update(&mut self, viewpoint: &Viewpoint) -> LodUpdate {
+        let selected: BTreeSet<CellId> = self.select(viewpoint).into_iter().collect();
+        let chunks: BTreeMap<CellId, u8> = selected
+            .iter()
+            .map(|cell| (*cell, stitch_mask(&selected, *cell)))
+            .collect();
+
+        let mut update = LodUpdate::default();
+        for (cell, mask) in &chunks {
+            match self.chunks.get(cell) {
+                None => update.added.push(*cell),
+                Some(previous) if self.settings.seams == LodSeams::Stitch && previous != mask => {
+                    update.restitched.push(*cell)
+                }
+                Some(_) => {}
+            }
+        }
+        update.removed = self
+            .chunks
+            .keys()
+            .filter(|cell| !chunks.contains_key(cell))
+            .copied()
+            .collect();
+
+        self.chunks = chunks;
+        update
+    }
+
+    /// generate the mesh of a patch, stitched to its current neighbors
+    /// ## Arguments
+    /// - `cell` - the patch, usually one of [`LodQuadtree::chunks`]
+    /// ## Return
+    /// - the mesh, or the value of the attribute hook not matching its format
+    pub fn chunk_mesh(&self, cell: CellId) -> Result<Mesh, CubeSphereError> {
+        let seam = self
+            .settings
+            .patch_seam(self.stitch_mask(cell).unwrap_or(0));
+        self.sphere
+            .try_patch_mesh(cell, self.settings.patch_resolution, seam)
+    }
+
+    /// the error of a patch seen from a viewpoint, in the unit of [`LodSettings::max_error`]
+    /// ## Arguments
+    /// - `cell` - the patch
+    /// - `viewpoint` - where the sphere is seen from
+    pub fn chunk_error(&self, cell: CellId, viewpoint: &Viewpoint) -> f32 {
+        let [min, max] = cell.face_bounds();
+        let corners = [
+            [min[0], min[1]],
+            [max[0], min[1]],
+            [max[0], max[1]],
+            [min[0], max[1]],
+        ]
+        .map(|coords| self.surface_point(cell.face(), coords));
+        let center = self
+            .sphere
+            .surface_point(&cell.center(self.sphere.projection.as_ref()));
+
+        let edge = (0..4)
+            .map(|k| distance(&corners[k], &corners[(k + 1) % 4]))
+            .fold(0., f32::max);
+        let bound = corners
+            .iter()
+            .map(|corner| distance(corner, &center))
+            .fold(0., f32::max);
+
+        // the closest point of the bounding sphere of the patch, never closer
+        // than a tiny fraction of the patch so that the error stays finite
+        let spacing = edge / (self.settings.patch_resolution - 1) as f32;
+        let range = (distance(&viewpoint.position, &center) - bound).max(spacing * 1e-3);
+        spacing * viewpoint.projection_scale / range
+    }
+
+    /// split a cell until its error is small enough, depth first
+    fn select_cell(&self, cell: CellId, viewpoint: &Viewpoint, selected: &mut Vec<CellId>) {
+        let split = cell.level() < self.settings.max_level
+            && self.chunk_error(cell, viewpoint) > self.settings.max_error;
+
+        match cell.children() {
+            Some(children) if split => {
+                for child in children {
+                    self.select_cell(child, viewpoint, selected);
+                }
+            }
+            _ => selected.push(cell),
+        }
+    }
+
+    /// the point of the surface at face coordinates
+    fn surface_point(&self, face: CubeFace, coords: [f32; 2]) -> [f32; 3] {
+        let unit_cube_point = face_coords_to_unit_cube_point(face, coords);
+        let unit_sphere_point = self.sphere.projection.cube_to_sphere(&unit_cube_point);
+        self.sphere.surface_point(&unit_sphere_point)
+    }
+}
+
+/// split the patches until every two patches sharing an edge differ by at most one level
+fn balance(selected: &mut BTreeSet<CellId>) {
+    let mut pending: Vec<CellId> = selected.iter().copied().collect();
+    while let Some(cell) = pending.pop() {
+        if !selected.contains(&cell) {
+            continue;
+        }
+
+        for neighbor in cell.edge_neighbors() {
+            let coarse = match covering(selected, neighbor) {
+                Some(coarse) if coarse.level() + 1 < cell.level() => coarse,
+                _ => continue,
+            };
+
+            let children = coarse.children().expect("a coarser cell has children");
+            selected.remove(&coarse);
+            selected.extend(children);
+            pending.extend(children);
+            // the children may still be too coarse for this cell
+            pending.push(cell);
+            break;
+        }
+    }
+}
+
+/// the edges of a patch bordering a coarser patch, as in [`LodQuadtree::stitch_mask`]
+fn stitch_mask(selected: &BTreeSet<CellId>, cell: CellId) -> u8 {
+    let mut mask = 0;
+    for (edge, neighbor) in FaceEdge::ALL.into_iter().zip(cell.edge_neighbors()) {
+        if matches!(covering(selected, neighbor), Some(c) if c.level() < cell.level()) {
+            mask |= 1 << edge.index();
+        }
+    }
+    mask
+}
+
+/// the selected patch containing a cell, `None` if the cell is split further
+fn covering(selected: &BTreeSet<CellId>, cell: CellId) -> Option<CellId> {
+    (0..=cell.level())
+        .rev()
+        .map(|level| cell.parent_at(level))
+        .find(|parent| selected.contains(parent))
+}
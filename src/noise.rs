@@ -0,0 +1,311 @@
+use crate::elevation::Elevation;
+
+/// the gradient noise summed by every octave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NoiseBasis {
+    /// improved Perlin noise, on a cubic lattice
+    Perlin,
+    /// simplex noise, cheaper and without the axis aligned artifacts of Perlin
+    Simplex,
+}
+
+/// how the octaves are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fractal {
+    /// fractional Brownian motion, rolling hills
+    Fbm,
+    /// ridged multifractal of Musgrave, sharp mountain ranges whose detail
+    /// fades in the valleys
+    Ridged,
+    /// the absolute value of every octave, puffy rounded shapes
+    Billow,
+}
+
+/// a seeded fractal noise evaluated on the direction from the center of the
+/// sphere, so the terrain does not depend on the faces of the cube
+///
+/// the same settings give the same terrain on every platform, and can be
+/// used as the [`crate::CubeSphere::elevation`] directly, or sampled with
+/// [`NoiseSettings::sample`] to bake textures
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct NoiseSettings {
+    /// the seed of the noise, every octave and warp axis derives its own from it
+    pub seed: u32,
+    /// the gradient noise of every octave
+    pub basis: NoiseBasis,
+    /// how the octaves are combined
+    pub fractal: Fractal,
+    /// the number of octaves
+    pub octaves: u32,
+    /// the frequency of the first octave, in features per unit of direction
+    pub frequency: f32,
+    /// the frequency ratio between two successive octaves
+    pub lacunarity: f32,
+    /// the amplitude ratio between two successive octaves
+    pub persistence: f32,
+    /// the height of the terrain, the noise stays within about `[-amplitude, amplitude]`
+    pub amplitude: f32,
+    /// how far the direction is moved by a fbm before sampling, `0` to disable domain warping
+    pub warp: f32,
+    /// the frequency of the fbm moving the direction
+    pub warp_frequency: f32,
+}
+
+impl Default for NoiseSettings {
+    fn default() -> Self {
+        NoiseSettings {
+            seed: 0,
+            basis: NoiseBasis::Simplex,
+            fractal: Fractal::Fbm,
+            octaves: 6,
+            frequency: 1.5,
+            lacunarity: 2.,
+            persistence: 0.5,
+            amplitude: 0.05,
+            warp: 0.,
+            warp_frequency: 1.,
+        }
+    }
+}
+
+/// the number of octaves of the fbm moving the direction
+const WARP_OCTAVES: u32 = 3;
+
+impl NoiseSettings {
+    /// evaluate the noise
+    /// ## Arguments
+    /// - `dir` - the direction from the center of the sphere, usually unit length
+    /// ## Return
+    /// - the height, range of about `[-amplitude, amplitude]`, the basis noises
+    ///   are not bounded exactly so it may overshoot a little
+    pub fn sample(&self, dir: &[f32; 3]) -> f32 {
+        let mut p = *dir;
+        if self.warp != 0. {
+            let offset = [0, 1, 2].map(|axis| {
+                let seed = self.seed ^ 0x9e37_79b9_u32.wrapping_mul(axis + 1);
+                let warp_point = p.map(|c| c * self.warp_frequency);
+                fbm(self.basis, &warp_point, seed, WARP_OCTAVES, 2., 0.5)
+            });
+            p = [0, 1, 2].map(|k| p[k] + self.warp * offset[k]);
+        }
+
+        let p = p.map(|c| c * self.frequency);
+        let value = match self.fractal {
+            Fractal::Fbm => fbm(
+                self.basis,
+                &p,
+                self.seed,
+                self.octaves,
+                self.lacunarity,
+                self.persistence,
+            ),
+            Fractal::Ridged => self.ridged(&p),
+            Fractal::Billow => self.billow(&p),
+        };
+        self.amplitude * value
+    }
+
+    /// the ridged multifractal, every octave is weighted by the previous one
+    fn ridged(&self, p: &[f32; 3]) -> f32 {
+        let mut sum = 0.;
+        let mut total = 0.;
+        let mut amplitude = 1.;
+        let mut frequency = 1.;
+        let mut weight = 1.;
+
+        for octave in 0..self.octaves {
+            let n = basis(
+                self.basis,
+                &p.map(|c| c * frequency),
+                octave_seed(self.seed, octave),
+            );
+            let signal = (1. - n.abs()).powi(2) * weight;
+            weight = (2. * signal).clamp(0., 1.);
+
+            sum += signal * amplitude;
+            total += amplitude;
+            amplitude *= self.persistence;
+            frequency *= self.lacunarity;
+        }
+
+        if total == 0. {
+            0.
+        } else {
+            2. * sum / total - 1.
+        }
+    }
+
+    /// the billow, the sum of the absolute values of the octaves
+    fn billow(&self, p: &[f32; 3]) -> f32 {
+        let mut sum = 0.;
+        let mut total = 0.;
+        let mut amplitude = 1.;
+        let mut frequency = 1.;
+
+        for octave in 0..self.octaves {
+            let n = basis(
+                self.basis,
+                &p.map(|c| c * frequency),
+                octave_seed(self.seed, octave),
+            );
+            sum += (2. * n.abs() - 1.) * amplitude;
+            total += amplitude;
+            amplitude *= self.persistence;
+            frequency *= self.lacunarity;
+        }
+
+        if total == 0. {
+            0.
+        } else {
+            sum / total
+        }
+    }
+}
+
+impl Elevation for NoiseSettings {
+    fn height(&self, dir: &[f32; 3]) -> f32 {
+        self.sample(dir)
+    }
+}
+
+/// the fractional Brownian motion, normalized to the range of about `[-1, 1]`
+fn fbm(
+    kind: NoiseBasis,
+    p: &[f32; 3],
+    seed: u32,
+    octaves: u32,
+    lacunarity: f32,
+    persistence: f32,
+) -> f32 {
+    let mut sum = 0.;
+    let mut total = 0.;
+    let mut amplitude = 1.;
+    let mut frequency = 1.;
+
+    for octave in 0..octaves {
+        sum += basis(kind, &p.map(|c| c * frequency), octave_seed(seed, octave)) * amplitude;
+        total += amplitude;
+        amplitude *= persistence;
+        frequency *= lacunarity;
+    }
+
+    if total == 0. {
+        0.
+    } else {
+        sum / total
+    }
+}
+
+fn basis(kind: NoiseBasis, p: &[f32; 3], seed: u32) -> f32 {
+    match kind {
+        NoiseBasis::Perlin => perlin(p, seed),
+        NoiseBasis::Simplex => simplex(p, seed),
+    }
+}
+
+fn octave_seed(seed: u32, octave: u32) -> u32 {
+    hash([octave as i32, 0, 0], seed)
+}
+
+/// the midpoints of the edges of a cube, the gradients of improved Perlin noise
+const GRADIENTS: [[f32; 3]; 12] = [
+    [1., 1., 0.],
+    [-1., 1., 0.],
+    [1., -1., 0.],
+    [-1., -1., 0.],
+    [1., 0., 1.],
+    [-1., 0., 1.],
+    [1., 0., -1.],
+    [-1., 0., -1.],
+    [0., 1., 1.],
+    [0., -1., 1.],
+    [0., 1., -1.],
+    [0., -1., -1.],
+];
+
+/// the pseudo-random gradient of a lattice point, dotted with an offset
+fn gradient(lattice: [i32; 3], seed: u32, offset: [f32; 3]) -> f32 {
+    let g = GRADIENTS[(hash(lattice, seed) % 12) as usize];
+    g[0] * offset[0] + g[1] * offset[1] + g[2] * offset[2]
+}
+
+/// improved Perlin noise, range of about `[-1, 1]`
+fn perlin(p: &[f32; 3], seed: u32) -> f32 {
+    let cell = p.map(|c| c.floor());
+    let lattice = cell.map(|c| c as i32);
+    let local = [0, 1, 2].map(|k| p[k] - cell[k]);
+    let fade = local.map(|t| t * t * t * (t * (t * 6. - 15.) + 10.));
+
+    let corner = |dx: i32, dy: i32, dz: i32| {
+        gradient(
+            [lattice[0] + dx, lattice[1] + dy, lattice[2] + dz],
+            seed,
+            [
+                local[0] - dx as f32,
+                local[1] - dy as f32,
+                local[2] - dz as f32,
+            ],
+        )
+    };
+    let lerp = |a: f32, b: f32, t: f32| a + t * (b - a);
+
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), fade[0]);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), fade[0]);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), fade[0]);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), fade[0]);
+    let y0 = lerp(x00, x10, fade[1]);
+    let y1 = lerp(x01, x11, fade[1]);
+    lerp(y0, y1, fade[2])
+}
+
+/// simplex noise, range of about `[-1, 1]`
+fn simplex(p: &[f32; 3], seed: u32) -> f32 {
+    const SKEW: f32 = 1. / 3.;
+    const UNSKEW: f32 = 1. / 6.;
+
+    // the simplex cell containing the point, in the skewed lattice
+    let s = (p[0] + p[1] + p[2]) * SKEW;
+    let cell = p.map(|c| (c + s).floor());
+    let t = (cell[0] + cell[1] + cell[2]) * UNSKEW;
+    let d0 = [0, 1, 2].map(|k| p[k] - (cell[k] - t));
+
+    // walk from the origin of the cell to its far corner along the largest
+    // coordinates first
+    let mut order = [0, 1, 2];
+    order.sort_by(|a, b| d0[*b].total_cmp(&d0[*a]));
+    let mut steps = [[0_i32; 3]; 4];
+    for k in 0..3 {
+        steps[k + 1] = steps[k];
+        steps[k + 1][order[k]] = 1;
+    }
+
+    let lattice = cell.map(|c| c as i32);
+    let mut sum = 0.;
+    for (k, step) in steps.iter().enumerate() {
+        let d = [0, 1, 2].map(|axis| d0[axis] - step[axis] as f32 + k as f32 * UNSKEW);
+        let falloff = 0.6 - d[0] * d[0] - d[1] * d[1] - d[2] * d[2];
+        if falloff > 0. {
+            let corner = [0, 1, 2].map(|axis| lattice[axis] + step[axis]);
+            sum += falloff.powi(4) * gradient(corner, seed, d);
+        }
+    }
+    32. * sum
+}
+
+/// hash a lattice point, the murmur3 finalizer over the mixed coordinates
+fn hash(lattice: [i32; 3], seed: u32) -> u32 {
+    let mut h = seed
+        ^ (lattice[0] as u32).wrapping_mul(0x8da6_b343)
+        ^ (lattice[1] as u32).wrapping_mul(0xd816_3841)
+        ^ (lattice[2] as u32).wrapping_mul(0xcb1a_b31f);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use bevy::prelude::Mesh;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy::render::render_resource::PrimitiveTopology;
+
+use crate::vector::{cross, dot, normalize, sub};
+use crate::{CubeSphere, CubeSphereError};
+
+impl CubeSphere {
+    /// recompute the normals and tangents of a mesh generated by this sphere
+    /// from its positions, e.g. after displacing the vertices
+    ///
+    /// the normal of a vertex averages the triangles around every vertex at
+    /// the same position weighted by their area, so the copies of a vertex on
+    /// a cube edge, a clip boundary or the antimeridian get the same normal.
+    /// The tangents follow the uv of their own triangles and are made
+    /// perpendicular to that shared normal, they are left as they are when
+    /// the mesh has no uv.
+    /// ## Arguments
+    /// - `mesh` - a triangle list, e.g. a mesh of [`CubeSphere::try_mesh`],
+    ///   [`CubeSphere::try_meshes`] or [`CubeSphere::try_patch_mesh`]
+    /// ## Return
+    /// - the reason the normals cannot be recomputed
+    pub fn recompute_normals(&self, mesh: &mut Mesh) -> Result<(), CubeSphereError> {
+        let count = mesh.count_vertices();
+        let mismatch = CubeSphereError::IncompatibleMesh(count);
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            return Err(mismatch);
+        }
+
+        let points = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(points)) => points.clone(),
+            _ => return Err(mismatch),
+        };
+        let indices: Vec<usize> = match mesh.indices() {
+            Some(indices) => indices.iter().collect(),
+            None => return Err(mismatch),
+        };
+        if !indices.len().is_multiple_of(3) || indices.iter().any(|i| *i >= count) {
+            return Err(mismatch);
+        }
+        let uvs = match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+            Some(VertexAttributeValues::Float32x2(uvs)) => Some(uvs.clone()),
+            _ => None,
+        };
+
+        // the copies of a vertex are bit-identical, whatever face they belong to
+        let mut positions = HashMap::new();
+        let groups: Vec<usize> = points
+            .iter()
+            .map(|point| {
+                let next = positions.len();
+                *positions.entry(point.map(f32::to_bits)).or_insert(next)
+            })
+            .collect();
+        let group_count = positions.len();
+
+        let mut group_normals = vec![[0_f32; 3]; group_count];
+        let mut tangents = vec![[0_f32; 3]; count];
+        let mut bitangents = vec![[0_f32; 3]; count];
+
+        for triangle in indices.chunks(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+            let e1 = sub(&points[b], &points[a]);
+            let e2 = sub(&points[c], &points[a]);
+
+            // twice the area along the normal of the triangle
+            let normal = cross(&e1, &e2);
+            for v in triangle {
+                add_to(&mut group_normals[groups[*v]], &normal);
+            }
+
+            if let Some(uvs) = &uvs {
+                let d1 = [uvs[b][0] - uvs[a][0], uvs[b][1] - uvs[a][1]];
+                let d2 = [uvs[c][0] - uvs[a][0], uvs[c][1] - uvs[a][1]];
+                let det = d1[0] * d2[1] - d2[0] * d1[1];
+                if det == 0. {
+                    continue;
+                }
+
+                // the derivatives of the position along u and v, scaled by
+                // the uv area of the triangle so that small triangles weigh less
+                let weight = det.signum();
+                let tangent = [0, 1, 2].map(|k| (e1[k] * d2[1] - e2[k] * d1[1]) * weight);
+                let bitangent = [0, 1, 2].map(|k| (e2[k] * d1[0] - e1[k] * d2[0]) * weight);
+                for v in triangle {
+                    add_to(&mut tangents[*v], &tangent);
+                    add_to(&mut bitangents[*v], &bitangent);
+                }
+            }
+        }
+
+        let normals: Vec<[f32; 3]> = groups
+            .iter()
+            .map(|g| normalize(&group_normals[*g]))
+            .collect();
+
+        if uvs.is_some() {
+            let tangents: Vec<[f32; 4]> = (0..count)
+                .map(|v| {
+                    let normal = &normals[v];
+                    let along = dot(&tangents[v], normal);
+                    let t = normalize(&[0, 1, 2].map(|k| tangents[v][k] - along * normal[k]));
+                    let w = if dot(&cross(normal, &t), &bitangents[v]) < 0. {
+                        -1.
+                    } else {
+                        1.
+                    };
+                    [t[0], t[1], t[2], w]
+                })
+                .collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_TANGENT, tangents);
+        }
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+
+        Ok(())
+    }
+}
+
+fn add_to(acc: &mut [f32; 3], v: &[f32; 3]) {
+    for k in 0..3 {
+        acc[k] += v[k];
+    }
+}
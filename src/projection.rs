@@ -0,0 +1,252 @@
+use std::f32::consts::{FRAC_PI_4, PI, SQRT_2};
+use std::fmt;
+
+use crate::vector::normalize;
+
+/// a mapping from the surface of the unit cube to the unit sphere
+pub trait CubeProjection: fmt::Debug + Send + Sync {
+    /// convert a unit cube vertex to a unit sphere vertex
+    /// ## Arguments
+    /// - `pt` - the location of cube vertex on the surface of the cube, range of `[-1, 1]`
+    /// ## Return
+    /// - the location of sphere vertex, range of `[-1, 1]`
+    fn cube_to_sphere(&self, pt: &[f32; 3]) -> [f32; 3];
+
+    /// convert a unit sphere vertex back to a unit cube vertex, the default
+    /// refines the straight projection with Newton's method and assumes every
+    /// face covers the same part of the sphere as with [`Normalized`]
+    /// ## Arguments
+    /// - `pt` - the location of sphere vertex, range of `[-1, 1]`
+    /// ## Return
+    /// - the location of cube vertex on the surface of the cube, range of `[-1, 1]`
+    fn sphere_to_cube(&self, pt: &[f32; 3]) -> [f32; 3] {
+        let (axis, [i, j]) = face_axes(pt);
+        let mut cube = Normalized.sphere_to_cube(pt);
+
+        const STEP: f32 = 1e-3;
+        for _ in 0..8 {
+            let sphere = self.cube_to_sphere(&cube);
+            let residual = [sphere[i] - pt[i], sphere[j] - pt[j]];
+            if residual[0].abs().max(residual[1].abs()) < 1e-7 {
+                break;
+            }
+
+            // finite difference jacobian of the coordinates along the face
+            let mut jacobian = [[0.; 2]; 2];
+            for (column, k) in [i, j].into_iter().enumerate() {
+                let mut moved = cube;
+                moved[k] += if moved[k] > 0. { -STEP } else { STEP };
+                let delta = moved[k] - cube[k];
+                let sphere_moved = self.cube_to_sphere(&moved);
+                jacobian[0][column] = (sphere_moved[i] - sphere[i]) / delta;
+                jacobian[1][column] = (sphere_moved[j] - sphere[j]) / delta;
+            }
+
+            let det = jacobian[0][0] * jacobian[1][1] - jacobian[0][1] * jacobian[1][0];
+            if det.abs() < f32::EPSILON {
+                break;
+            }
+            let da = (jacobian[1][1] * residual[0] - jacobian[0][1] * residual[1]) / det;
+            let db = (jacobian[0][0] * residual[1] - jacobian[1][0] * residual[0]) / det;
+            cube[i] = (cube[i] - da).clamp(-1., 1.);
+            cube[j] = (cube[j] - db).clamp(-1., 1.);
+        }
+
+        cube[axis] = pt[axis].signum();
+        cube
+    }
+}
+
+/// project the cube vertex straight onto the sphere, cells near the
+/// center of a face are about 5 times larger than the ones in the corners
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Normalized;
+
+impl CubeProjection for Normalized {
+    fn cube_to_sphere(&self, pt: &[f32; 3]) -> [f32; 3] {
+        normalize(pt)
+    }
+
+    fn sphere_to_cube(&self, pt: &[f32; 3]) -> [f32; 3] {
+        let (axis, _) = face_axes(pt);
+        let scale = pt[axis].abs();
+        let mut cube = pt.map(|c| (c / scale).clamp(-1., 1.));
+        cube[axis] = pt[axis].signum();
+        cube
+    }
+}
+
+/// the spherified cube of
+/// [catlikecoding](https://catlikecoding.com/unity/tutorials/cube-sphere/),
+/// the default projection
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Spherified;
+
+impl CubeProjection for Spherified {
+    fn cube_to_sphere(&self, pt: &[f32; 3]) -> [f32; 3] {
+        let x = pt[0];
+        let y = pt[1];
+        let z = pt[2];
+
+        let x2 = x.powi(2);
+        let y2 = y.powi(2);
+        let z2 = z.powi(2);
+
+        let nx = x * (1. - y2 / 2. - z2 / 2. + y2 * z2 / 3.).sqrt();
+        let ny = y * (1. - x2 / 2. - z2 / 2. + x2 * z2 / 3.).sqrt();
+        let nz = z * (1. - x2 / 2. - y2 / 2. + x2 * y2 / 3.).sqrt();
+
+        [nx, ny, nz]
+    }
+
+    fn sphere_to_cube(&self, pt: &[f32; 3]) -> [f32; 3] {
+        let (axis, [i, j]) = face_axes(pt);
+        let x2 = (pt[i] as f64).powi(2);
+        let y2 = (pt[j] as f64).powi(2);
+
+        // squaring the formula of `cube_to_sphere` along the face gives a
+        // quadratic equation in `a²`, its small root is taken in the form
+        // that does not cancel near the center of the face
+        let root = |x2: f64, y2: f64| {
+            let q = 3. + 2. * x2 - 2. * y2;
+            let d = (q.powi(2) - 24. * x2).max(0.);
+            (12. * x2 / (q + d.sqrt())).sqrt().min(1.)
+        };
+
+        let mut cube = [0.; 3];
+        cube[axis] = pt[axis].signum();
+        cube[i] = root(x2, y2) as f32 * pt[i].signum();
+        cube[j] = root(y2, x2) as f32 * pt[j].signum();
+        cube
+    }
+}
+
+/// the tangent (equal-angle) warp, every grid line of a face is spaced by the
+/// same angle as seen from the center of the sphere
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tangent;
+
+impl CubeProjection for Tangent {
+    fn cube_to_sphere(&self, pt: &[f32; 3]) -> [f32; 3] {
+        normalize(&warp(pt, |s| (s * FRAC_PI_4).tan()))
+    }
+
+    fn sphere_to_cube(&self, pt: &[f32; 3]) -> [f32; 3] {
+        warp(&Normalized.sphere_to_cube(pt), |g| g.atan() / FRAC_PI_4)
+    }
+}
+
+/// the quadratic warp of Everitt, close to the tangent warp but cheaper to
+/// evaluate and to invert
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Everitt;
+
+/// the parameter of the Everitt warp, as fitted by Zucker and Higashi
+const EVERITT_EPSILON: f32 = 1.4511;
+
+impl CubeProjection for Everitt {
+    fn cube_to_sphere(&self, pt: &[f32; 3]) -> [f32; 3] {
+        let e = EVERITT_EPSILON;
+        normalize(&warp(pt, |s| {
+            s.signum() * (e - (e.powi(2) - 4. * (e - 1.) * s.abs()).sqrt()) / (2. * (e - 1.))
+        }))
+    }
+
+    fn sphere_to_cube(&self, pt: &[f32; 3]) -> [f32; 3] {
+        let e = EVERITT_EPSILON;
+        warp(&Normalized.sphere_to_cube(pt), |g| {
+            g * (e + (1. - e) * g.abs())
+        })
+    }
+}
+
+/// the quadrilateralized spherical cube of O'Neill and Laubscher (as used by
+/// COBE), every cell of a face covers exactly the same area of the sphere
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Quadrilateralized;
+
+impl CubeProjection for Quadrilateralized {
+    fn cube_to_sphere(&self, pt: &[f32; 3]) -> [f32; 3] {
+        let (axis, [i, j]) = face_axes(pt);
+        let (a, b) = (pt[i], pt[j]);
+
+        // the mapping is defined on the triangle `|b| <= |a|`, mirrored onto the others
+        let swap = b.abs() > a.abs();
+        let (a, b) = if swap { (b, a) } else { (a, b) };
+
+        let mut local = [0., 0., 1.];
+        if a != 0. {
+            // the ray `b / a` maps to an azimuth around the face center, so that
+            // the area on its side of the triangle is kept
+            let psi = PI / 12. * (b / a).abs();
+            let phi = psi + (psi.sin() / (SQRT_2 - psi.cos())).atan();
+
+            // the distance along the ray maps to the polar angle, so that the
+            // area enclosed by the ray up to that distance is kept
+            let cos_edge = 1. / (2. + phi.tan().powi(2)).sqrt();
+            let cos_theta = 1. - a.powi(2) * (1. - cos_edge);
+            let sin_theta = (1. - cos_theta.powi(2)).max(0.).sqrt();
+
+            local = [
+                sin_theta * phi.cos() * a.signum(),
+                sin_theta * phi.sin() * b.signum(),
+                cos_theta,
+            ];
+        }
+        if swap {
+            local.swap(0, 1);
+        }
+
+        let mut result = [0.; 3];
+        result[axis] = local[2] * pt[axis].signum();
+        result[i] = local[0];
+        result[j] = local[1];
+        result
+    }
+
+    fn sphere_to_cube(&self, pt: &[f32; 3]) -> [f32; 3] {
+        let (axis, [i, j]) = face_axes(pt);
+        let (x, y) = (pt[i], pt[j]);
+
+        let swap = y.abs() > x.abs();
+        let (x, y) = if swap { (y, x) } else { (x, y) };
+
+        let mut local = [0., 0.];
+        if x != 0. {
+            let phi = (y / x).abs().atan();
+            let t = 12. / PI * (phi - (phi.sin() / SQRT_2).asin());
+
+            let cos_edge = 1. / (2. + phi.tan().powi(2)).sqrt();
+            let cos_theta = pt[axis].abs();
+            let a = ((1. - cos_theta) / (1. - cos_edge)).sqrt().min(1.);
+
+            local = [a * x.signum(), (a * t).min(a) * y.signum()];
+        }
+        if swap {
+            local.swap(0, 1);
+        }
+
+        let mut result = [0.; 3];
+        result[axis] = pt[axis].signum();
+        result[i] = local[0];
+        result[j] = local[1];
+        result
+    }
+}
+
+/// split a point on the cube into the axis of its face and the two axes along the face
+fn face_axes(pt: &[f32; 3]) -> (usize, [usize; 2]) {
+    let abs = pt.map(f32::abs);
+    if abs[0] >= abs[1] && abs[0] >= abs[2] {
+        (0, [1, 2])
+    } else if abs[1] >= abs[2] {
+        (1, [0, 2])
+    } else {
+        (2, [0, 1])
+    }
+}
+
+/// apply a warp to the coordinates along the face, the edges of the face are kept as they are
+fn warp(pt: &[f32; 3], f: impl Fn(f32) -> f32) -> [f32; 3] {
+    pt.map(|c| if c.abs() < 1. { f(c) } else { c })
+}
@@ -0,0 +1,547 @@
+use std::f32::consts::{PI, TAU};
+use std::fmt;
+
+use float_ord::FloatOrd;
+
+use crate::vector::{dot, length, normalize};
+use crate::{
+    face_coords_to_unit_cube_point, mirror_along_u, sphere_point_to_face_coords, CubeFace,
+    CubeProjection, CubeSphereError,
+};
+
+/// an arrangement of the six faces of the cube in a texture
+///
+/// the uv coordinates and the tangents of the mesh and the procedural texture
+/// helpers of [`crate::CubeSphere`] all go through the layout, so they agree
+/// whatever layout is plugged in
+pub trait UvLayout: fmt::Debug + Send + Sync {
+    /// compute the uv coordinate of a vertex of a face
+    ///
+    /// the layout is sampled slightly beyond the borders of the face to
+    /// derive the tangents, so it should extend smoothly there
+    /// ## Arguments
+    /// - `cube_point` - the location of the vertex on the cube, range of `[-1, 1]`
+    /// - `sphere_point` - the location of the vertex on the unit sphere
+    /// - `face` - the face that this vertex belongs to, a vertex on a border
+    ///   gets the uv of every face it belongs to
+    /// ## Return
+    /// - the uv coordinate, range of `[0, 1]`
+    fn point_to_uv(
+        &self,
+        cube_point: &[f32; 3],
+        sphere_point: &[f32; 3],
+        face: CubeFace,
+    ) -> [f32; 2];
+
+    /// the faces whose part of the texture shows a point, the faces the point
+    /// lies on by default
+    /// ## Arguments
+    /// - `cube_point` - the location of the point on the cube, range of `[-1, 1]`
+    /// - `sphere_point` - the location of the point on the unit sphere
+    fn point_faces(&self, cube_point: &[f32; 3], sphere_point: &[f32; 3]) -> Vec<CubeFace> {
+        let _ = sphere_point;
+        CubeFace::ALL
+            .into_iter()
+            .filter(|f| dot(&f.normal(), cube_point) >= 1.)
+            .collect()
+    }
+
+    /// compute the unit sphere vertex shown at a uv coordinate, the inverse
+    /// of [`UvLayout::point_to_uv`]
+    /// ## Arguments
+    /// - `uv` - the uv coordinate, range of `[0, 1]`
+    /// - `projection` - the projection of the sphere, for the layouts
+    ///   following the cube
+    /// ## Return
+    /// - the face whose part of the texture holds the coordinate, and the unit
+    ///   sphere vertex, `None` means that uv coordinate map to nowhere on the
+    ///   surface of the sphere
+    fn uv_to_point(
+        &self,
+        uv: &[f32; 2],
+        projection: &dyn CubeProjection,
+    ) -> Option<(CubeFace, [f32; 3])>;
+
+    /// compute the unit sphere vertex shown at a uv coordinate within the
+    /// part of the texture of a face, for the layouts whose uv does not tell
+    /// the face
+    /// ## Arguments
+    /// - `uv` - the uv coordinate, range of `[0, 1]`
+    /// - `face` - the face whose part of the texture holds the coordinate
+    /// - `projection` - the projection of the sphere
+    /// ## Return
+    /// - the unit sphere vertex, `None` means that uv coordinate map to
+    ///   nowhere on that face
+    fn face_uv_to_point(
+        &self,
+        uv: &[f32; 2],
+        face: CubeFace,
+        projection: &dyn CubeProjection,
+    ) -> Option<[f32; 3]> {
+        match self.uv_to_point(uv, projection) {
+            Some((f, pt)) if f == face => Some(pt),
+            _ => None,
+        }
+    }
+
+    /// whether the uv of a vertex does not tell its face, the mesh then
+    /// carries the face in [`crate::ATTRIBUTE_CUBE_FACE`]
+    fn face_attribute(&self) -> bool {
+        false
+    }
+
+    /// whether `u` wraps around along the antimeridian, the half plane
+    /// `x = 0, z < 0`, from `1` on its east side to `0` on its west side. The
+    /// mesh is then cut along it with its vertices duplicated, and a vertex on
+    /// a pole gets the `u` of each of its triangles
+    fn antimeridian(&self) -> bool {
+        false
+    }
+
+    /// mirror a point so that its `u` is mirrored, for the inside of an
+    /// inverted sphere, across the plane of the normal and the second axis of
+    /// the face by default
+    /// ## Arguments
+    /// - `pt` - the location of the point on the cube or on the sphere
+    /// - `face` - the face of the point
+    fn mirror(&self, pt: &[f32; 3], face: CubeFace) -> [f32; 3] {
+        mirror_along_u(pt, face)
+    }
+
+    /// check that the settings of the layout are within their range, before
+    /// any mesh is generated with it
+    /// ## Return
+    /// - the reason the layout cannot be used, always valid by default
+    fn validate(&self) -> Result<(), CubeSphereError> {
+        Ok(())
+    }
+}
+
+/// every face shows the hemisphere around it as a disc, the six discs are
+/// [optimally packed](http://hydra.nat.uni-magdeburg.de/packing/csq/csq.html)
+/// in the texture, the default layout
+///
+/// every point of the sphere is on three discs, one per axis, so the texture
+/// of a face continues past its borders
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiscPacking;
+
+const UV_SPHERE_RADIUS: f32 = 0.1876806;
+const UV_COORDINATE_0: [f32; 2] = [0.0000000, 0.0000000];
+const UV_COORDINATE_1: [f32; 2] = [0.6246388, 0.0000000];
+const UV_COORDINATE_2: [f32; 2] = [0.3123194, 0.2082129];
+const UV_COORDINATE_3: [f32; 2] = [0.0000000, 0.4164259];
+const UV_COORDINATE_4: [f32; 2] = [0.6246388, 0.4164259];
+const UV_COORDINATE_5: [f32; 2] = [0.3123194, 0.6246388];
+
+/// how far outside of its disc a uv coordinate is still on the sphere, the
+/// rounding of a point on the rim of the disc
+const UV_RIM_TOLERANCE: f32 = 1e-5;
+
+impl UvLayout for DiscPacking {
+    fn point_to_uv(&self, _: &[f32; 3], sphere_point: &[f32; 3], face: CubeFace) -> [f32; 2] {
+        unit_sphere_point_to_uv(sphere_point, face)
+    }
+
+    /// the discs of the three faces on the side of the point along each axis,
+    /// in the order of the axes
+    fn point_faces(&self, _: &[f32; 3], sphere_point: &[f32; 3]) -> Vec<CubeFace> {
+        // the sign bit picks the side of a point on the rim of a disc, so
+        // that its uv maps back to it
+        let [x, y, z] = sphere_point.map(f32::is_sign_positive);
+        vec![
+            if x { CubeFace::Right } else { CubeFace::Left },
+            if y { CubeFace::Top } else { CubeFace::Bottom },
+            if z { CubeFace::Front } else { CubeFace::Back },
+        ]
+    }
+
+    fn uv_to_point(&self, uv: &[f32; 2], _: &dyn CubeProjection) -> Option<(CubeFace, [f32; 3])> {
+        let face = CubeFace::ALL
+            .into_iter()
+            .min_by_key(|f| {
+                let center = uv_disc_center(*f);
+                FloatOrd((uv[0] - center[0]).powi(2) + (uv[1] - center[1]).powi(2))
+            })
+            .unwrap();
+        uv_to_unit_sphere_point(uv, face).map(|pt| (face, pt))
+    }
+}
+
+/// face and its corresponding uv coordinate on texture
+/// ## Arguments
+/// - `f` - the face
+/// ## Return
+/// - the uv coordinate of this specific face
+fn face_to_uv_coordinate(f: CubeFace) -> [f32; 2] {
+    match f {
+        CubeFace::Front => UV_COORDINATE_0,
+        CubeFace::Back => UV_COORDINATE_3,
+        CubeFace::Left => UV_COORDINATE_2,
+        CubeFace::Right => UV_COORDINATE_5,
+        CubeFace::Top => UV_COORDINATE_1,
+        CubeFace::Bottom => UV_COORDINATE_4,
+    }
+}
+
+/// compute uv coordinate for a unit sphere vertex
+/// ## Arguments
+/// - `pt` - the location of sphere vertex, range of `[-1, 1]`
+/// - `f` - the face that this vertex belongs to in its original cube
+/// ## Return
+/// - the uv coordinate on that specific vertex
+fn unit_sphere_point_to_uv(pt: &[f32; 3], f: CubeFace) -> [f32; 2] {
+    let x = pt[0];
+    let y = pt[1];
+    let z = pt[2];
+
+    fn scale(i: f32) -> f32 {
+        (i + 1.) * UV_SPHERE_RADIUS
+    }
+
+    fn flip(i: f32) -> f32 {
+        UV_SPHERE_RADIUS * 2. - i
+    }
+
+    let coord = face_to_uv_coordinate(f);
+
+    match f {
+        CubeFace::Front => [coord[0] + scale(x), 1. - (coord[1] + scale(y))],
+        CubeFace::Back => [coord[0] + flip(scale(x)), 1. - (coord[1] + scale(y))],
+        CubeFace::Left => [coord[0] + scale(z), 1. - (coord[1] + scale(y))],
+        CubeFace::Right => [coord[0] + flip(scale(z)), 1. - (coord[1] + scale(y))],
+        CubeFace::Top => [coord[0] + scale(x), 1. - (coord[1] + flip(scale(z)))],
+        CubeFace::Bottom => [coord[0] + scale(x), 1. - (coord[1] + scale(z))],
+    }
+}
+
+/// the center of the disc of a face in the texture
+fn uv_disc_center(f: CubeFace) -> [f32; 2] {
+    let coord = face_to_uv_coordinate(f);
+    [
+        coord[0] + UV_SPHERE_RADIUS,
+        1. - (coord[1] + UV_SPHERE_RADIUS),
+    ]
+}
+
+/// compute the unit sphere vertex of a uv coordinate on the disc of a face,
+/// the inverse of `unit_sphere_point_to_uv`
+/// ## Arguments
+/// - `uv` - the uv coordinate, range of `[0, 1]`
+/// - `f` - the face whose disc contains the coordinate
+/// ## Return
+/// - the unit sphere vertex on the side of the face, `None` if the coordinate
+///   is outside of the disc
+fn uv_to_unit_sphere_point(uv: &[f32; 2], f: CubeFace) -> Option<[f32; 3]> {
+    let coord = face_to_uv_coordinate(f);
+
+    // the inverse of `scale` and `flip`
+    fn unscale(s: f32) -> f32 {
+        s / UV_SPHERE_RADIUS - 1.
+    }
+
+    let s = uv[0] - coord[0];
+    let t = 1. - uv[1] - coord[1];
+    let (a, b) = match f {
+        CubeFace::Back | CubeFace::Right => (-unscale(s), unscale(t)),
+        CubeFace::Top => (unscale(s), -unscale(t)),
+        CubeFace::Front | CubeFace::Left | CubeFace::Bottom => (unscale(s), unscale(t)),
+    };
+
+    let w2 = 1. - a.powi(2) - b.powi(2);
+    if w2 < -UV_RIM_TOLERANCE {
+        return None;
+    }
+    let w = w2.max(0.).sqrt();
+
+    let pt = match f {
+        CubeFace::Front => [a, b, w],
+        CubeFace::Back => [a, b, -w],
+        CubeFace::Left => [-w, b, a],
+        CubeFace::Right => [w, b, a],
+        CubeFace::Top => [a, w, b],
+        CubeFace::Bottom => [a, -w, b],
+    };
+    Some(normalize(&pt))
+}
+
+/// how the tiles of [`FaceTiles`] are arranged in the texture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileArrangement {
+    /// 3 columns and 2 rows, `+x -x +y` above `-y +z -z`
+    Atlas3x2,
+    /// 4 columns and 3 rows, the side faces `-x +z +x -z` in the middle row,
+    /// `+y` above and `-y` below `+z`, the borders of adjacent tiles meet
+    Cross,
+    /// 6 columns and 1 row, `+x -x +y -y +z -z`
+    Strip,
+}
+
+impl TileArrangement {
+    /// the number of columns and rows of tiles
+    fn size(self) -> [u32; 2] {
+        match self {
+            TileArrangement::Atlas3x2 => [3, 2],
+            TileArrangement::Cross => [4, 3],
+            TileArrangement::Strip => [6, 1],
+        }
+    }
+
+    /// the column and the row of the tile of a face
+    fn tile(self, f: CubeFace) -> [u32; 2] {
+        let order = match f {
+            CubeFace::Right => 0,
+            CubeFace::Left => 1,
+            CubeFace::Top => 2,
+            CubeFace::Bottom => 3,
+            CubeFace::Front => 4,
+            CubeFace::Back => 5,
+        };
+        match self {
+            TileArrangement::Atlas3x2 => [order % 3, order / 3],
+            TileArrangement::Strip => [order, 0],
+            TileArrangement::Cross => match f {
+                CubeFace::Left => [0, 1],
+                CubeFace::Front => [1, 1],
+                CubeFace::Right => [2, 1],
+                CubeFace::Back => [3, 1],
+                CubeFace::Top => [1, 0],
+                CubeFace::Bottom => [1, 2],
+            },
+        }
+    }
+}
+
+/// every face is a square tile of the texture, as the faces of a cubemap,
+/// the cube coordinates `[-1, 1]` of the face spread evenly over its tile
+///
+/// a tile is seen from the outside of the sphere, `u` follows the first axis
+/// of the face and `v` runs against the second one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaceTiles {
+    /// how the tiles are arranged in the texture
+    pub arrangement: TileArrangement,
+    /// the margin left around the face within its tile, relative to the size
+    /// of the tile, range of `[0, 0.5)`, e.g. to keep the mipmaps of a face
+    /// from bleeding into its neighbors. Without a gutter, a border between
+    /// two tiles that do not meet on the sphere is shown by both
+    pub gutter: f32,
+}
+
+impl FaceTiles {
+    /// the faces in a 3x2 atlas, see [`TileArrangement::Atlas3x2`]
+    /// ## Arguments
+    /// - `gutter` - the margin around every face, relative to its tile
+    pub fn atlas(gutter: f32) -> Self {
+        FaceTiles {
+            arrangement: TileArrangement::Atlas3x2,
+            gutter,
+        }
+    }
+
+    /// the faces in a 4x3 horizontal cross, see [`TileArrangement::Cross`]
+    /// ## Arguments
+    /// - `gutter` - the margin around every face, relative to its tile
+    pub fn cross(gutter: f32) -> Self {
+        FaceTiles {
+            arrangement: TileArrangement::Cross,
+            gutter,
+        }
+    }
+
+    /// the faces in a 6x1 strip, see [`TileArrangement::Strip`]
+    /// ## Arguments
+    /// - `gutter` - the margin around every face, relative to its tile
+    pub fn strip(gutter: f32) -> Self {
+        FaceTiles {
+            arrangement: TileArrangement::Strip,
+            gutter,
+        }
+    }
+}
+
+impl UvLayout for FaceTiles {
+    fn point_to_uv(&self, cube_point: &[f32; 3], _: &[f32; 3], face: CubeFace) -> [f32; 2] {
+        let [columns, rows] = self.arrangement.size();
+        let [column, row] = self.arrangement.tile(face);
+        let span = 1. - 2. * self.gutter;
+        let [s, t] = face_square_uv(cube_point, face).map(|c| self.gutter + c * span);
+        [
+            (column as f32 + s) / columns as f32,
+            (row as f32 + t) / rows as f32,
+        ]
+    }
+
+    /// a coordinate in the gutter of a tile maps to the closest point of the
+    /// border of its face, so that a baked texture bleeds into the gutter
+    fn uv_to_point(
+        &self,
+        uv: &[f32; 2],
+        projection: &dyn CubeProjection,
+    ) -> Option<(CubeFace, [f32; 3])> {
+        let [columns, rows] = self.arrangement.size();
+        let x = uv[0] * columns as f32;
+        let y = uv[1] * rows as f32;
+        if !(0. ..=columns as f32).contains(&x) || !(0. ..=rows as f32).contains(&y) {
+            return None;
+        }
+
+        // a border between two tiles belongs to the tile after it, or to the
+        // tile before it when there is no face after it
+        fn tiles(p: f32, count: u32) -> [u32; 2] {
+            let k = (p as u32).min(count - 1);
+            if p == k as f32 && k > 0 {
+                [k, k - 1]
+            } else {
+                [k; 2]
+            }
+        }
+        let (face, [column, row]) = tiles(x, columns)
+            .into_iter()
+            .flat_map(|column| tiles(y, rows).map(|row| [column, row]))
+            .find_map(|tile| {
+                CubeFace::ALL
+                    .into_iter()
+                    .find(|f| self.arrangement.tile(*f) == tile)
+                    .map(|f| (f, tile))
+            })?;
+
+        let span = 1. - 2. * self.gutter;
+        let s = (x - column as f32 - self.gutter) / span;
+        let t = (y - row as f32 - self.gutter) / span;
+        Some((face, face_square_uv_to_point(&[s, t], face, projection)))
+    }
+
+    fn validate(&self) -> Result<(), CubeSphereError> {
+        if (0. ..0.5).contains(&self.gutter) {
+            Ok(())
+        } else {
+            Err(CubeSphereError::InvalidGutter(self.gutter))
+        }
+    }
+}
+
+/// every face covers the whole texture, `[0, 1]` along both axes, in its own
+/// layer of a texture array, the index of the face in `CubeFace::ALL`. The
+/// mesh carries the layer in [`crate::ATTRIBUTE_CUBE_FACE`]
+///
+/// a uv coordinate is shown by every layer, so
+/// [`crate::CubeSphere::uv_to_point`] gives `None` and the helpers take the
+/// face, see [`crate::CubeSphere::face_uv_to_point`]
+///
+/// a layer is seen from the outside of the sphere, `u` follows the first axis
+/// of the face and `v` runs against the second one
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FaceLayers;
+
+impl UvLayout for FaceLayers {
+    fn point_to_uv(&self, cube_point: &[f32; 3], _: &[f32; 3], face: CubeFace) -> [f32; 2] {
+        face_square_uv(cube_point, face)
+    }
+
+    /// always `None`, as the uv does not tell the layer, see
+    /// [`UvLayout::face_uv_to_point`]
+    fn uv_to_point(&self, _: &[f32; 2], _: &dyn CubeProjection) -> Option<(CubeFace, [f32; 3])> {
+        None
+    }
+
+    fn face_uv_to_point(
+        &self,
+        uv: &[f32; 2],
+        face: CubeFace,
+        projection: &dyn CubeProjection,
+    ) -> Option<[f32; 3]> {
+        let within = |c: f32| (0. ..=1.).contains(&c);
+        if within(uv[0]) && within(uv[1]) {
+            Some(face_square_uv_to_point(uv, face, projection))
+        } else {
+            None
+        }
+    }
+
+    fn face_attribute(&self) -> bool {
+        true
+    }
+}
+
+/// the longitude and the latitude of a point spread over the whole texture,
+/// as the equirectangular maps of planets, with the `y` axis pointing to the
+/// north pole and the prime meridian along the `z` axis in the middle
+///
+/// `u` grows eastward, from `0` on the west side of the antimeridian to `1`
+/// on its east side, and `v` grows southward. The mesh is cut along the
+/// antimeridian, so it has more vertices than the grid
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Equirectangular;
+
+impl UvLayout for Equirectangular {
+    fn point_to_uv(&self, _: &[f32; 3], sphere_point: &[f32; 3], _: CubeFace) -> [f32; 2] {
+        let [x, y, z] = *sphere_point;
+        let norm = length(sphere_point);
+
+        // a point on the antimeridian is on its east side, whatever the sign
+        // of its zero
+        let longitude = if x == 0. && z < 0. { PI } else { x.atan2(z) };
+        let latitude = (y / norm).clamp(-1., 1.).asin();
+        [0.5 + longitude / TAU, 0.5 - latitude / PI]
+    }
+
+    /// a point on the antimeridian has the uv of its east side only
+    fn uv_to_point(
+        &self,
+        uv: &[f32; 2],
+        projection: &dyn CubeProjection,
+    ) -> Option<(CubeFace, [f32; 3])> {
+        let within = |c: f32| (0. ..=1.).contains(&c);
+        if !within(uv[0]) || !within(uv[1]) {
+            return None;
+        }
+
+        let longitude = (uv[0] - 0.5) * TAU;
+        let latitude = (0.5 - uv[1]) * PI;
+        let pt = [
+            longitude.sin() * latitude.cos(),
+            latitude.sin(),
+            longitude.cos() * latitude.cos(),
+        ];
+        let (face, _) = sphere_point_to_face_coords(&pt, projection);
+        Some((face, pt))
+    }
+
+    fn antimeridian(&self) -> bool {
+        true
+    }
+
+    /// the texture covers the whole sphere, so `u` is mirrored across the
+    /// plane `x = 0` on every face
+    fn mirror(&self, pt: &[f32; 3], _: CubeFace) -> [f32; 3] {
+        [-pt[0], pt[1], pt[2]]
+    }
+}
+
+/// the location of a point within the square of its face, as seen from the
+/// outside of the sphere
+/// ## Arguments
+/// - `cube_point` - the location of the point on the cube, range of `[-1, 1]`
+/// - `f` - the face of the square
+/// ## Return
+/// - the coordinates from the top left corner of the square, range of `[0, 1]`
+fn face_square_uv(cube_point: &[f32; 3], f: CubeFace) -> [f32; 2] {
+    let [a, b] = f.axes().map(|axis| dot(cube_point, &axis));
+    [(a + 1.) / 2., (1. - b) / 2.]
+}
+
+/// the inverse of `face_square_uv`, the coordinates outside of the square are
+/// clamped to its border
+/// ## Arguments
+/// - `uv` - the coordinates from the top left corner of the square, range of `[0, 1]`
+/// - `f` - the face of the square
+/// - `projection` - the projection from the cube to the sphere
+fn face_square_uv_to_point(
+    uv: &[f32; 2],
+    f: CubeFace,
+    projection: &dyn CubeProjection,
+) -> [f32; 3] {
+    let coords = [2. * uv[0] - 1., 1. - 2. * uv[1]].map(|c| c.clamp(-1., 1.));
+    projection.cube_to_sphere(&face_coords_to_unit_cube_point(f, coords))
+}
@@ -0,0 +1,45 @@
+use std::ops::{Add, Mul, Sub};
+
+/// the dot product of two vectors
+pub(crate) fn dot<T>(a: &[T; 3], b: &[T; 3]) -> T
+where
+    T: Copy + Add<Output = T> + Mul<Output = T>,
+{
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// the cross product of two vectors, `a × b`
+pub(crate) fn cross<T>(a: &[T; 3], b: &[T; 3]) -> [T; 3]
+where
+    T: Copy + Sub<Output = T> + Mul<Output = T>,
+{
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// the difference of two vectors, `a - b`
+pub(crate) fn sub<T>(a: &[T; 3], b: &[T; 3]) -> [T; 3]
+where
+    T: Copy + Sub<Output = T>,
+{
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// the length of a vector
+pub(crate) fn length(v: &[f32; 3]) -> f32 {
+    dot(v, v).sqrt()
+}
+
+/// the distance between two points
+pub(crate) fn distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    length(&sub(a, b))
+}
+
+/// a vector scaled to unit length
+pub(crate) fn normalize(v: &[f32; 3]) -> [f32; 3] {
+    let norm = length(v);
+    v.map(|c| c / norm)
+}
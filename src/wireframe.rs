@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use bevy::prelude::Mesh;
+use bevy::render::render_resource::PrimitiveTopology;
+
+use crate::{CubeSphere, CubeSphereError, CUBE_FACE_COUNT};
+
+/// the grid lines drawn by [`CubeSphere::try_wireframe_mesh`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridLines {
+    /// every edge of the grid
+    All,
+    /// every `n`-th line of the grid of each face, the borders of the face
+    /// included, e.g. `1` for every edge
+    Every(usize),
+    /// the borders of the faces only, the edges of the cube
+    FaceBorders,
+}
+
+impl CubeSphere {
+    /// generate the grid of the sphere as a line list, for the backends
+    /// without `POLYGON_MODE_LINE` such as WebGL
+    ///
+    /// the vertices are the ones of [`CubeSphere::try_mesh`] in the same
+    /// order, with or without `welded`, so the lines can be drawn over the
+    /// triangles or share their vertex attributes. `clip` is ignored, the
+    /// lines cover the whole sphere, and so is the cut of the uv layouts
+    /// wrapping around the antimeridian, the vertices are the ones of the
+    /// face grids
+    /// ## Arguments
+    /// - `lines` - which lines of the grid to draw
+    /// ## Return
+    /// - the mesh, or the reason it cannot be generated
+    pub fn try_wireframe_mesh(&self, lines: GridLines) -> Result<Mesh, CubeSphereError> {
+        let spacing = match lines {
+            GridLines::All => 1,
+            GridLines::Every(0) => return Err(CubeSphereError::InvalidLineSpacing(0)),
+            GridLines::Every(spacing) => spacing,
+            GridLines::FaceBorders => self.resolution.max(2) - 1,
+        };
+
+        // the lines index the face grids, before any vertex is added or removed
+        let sphere = CubeSphere {
+            welded: false,
+            ..self.clone()
+        };
+        let mut data = sphere.build_mesh_data(false, &sphere.face_blocks())?;
+
+        let n = self.resolution as u32;
+        let mut indices = Vec::new();
+        for f in 0..CUBE_FACE_COUNT as u32 {
+            insert_grid_line_indices(n, spacing as u32, f * n.pow(2), &mut indices);
+        }
+
+        data.indices = indices;
+        if self.welded {
+            // the borders of two faces meet on the same welded vertices
+            data = data.welded();
+            let mut seen = HashSet::new();
+            data.indices = std::mem::take(&mut data.indices)
+                .chunks(2)
+                .filter(|line| seen.insert((line[0].min(line[1]), line[0].max(line[1]))))
+                .flatten()
+                .copied()
+                .collect();
+        }
+
+        data.into_mesh(PrimitiveTopology::LineList)
+    }
+}
+
+/// append the lines of a face grid
+/// ## Arguments
+/// - `n` - the number of vertices on the edge of the grid
+/// - `spacing` - draw every `spacing`-th line, and the last one
+/// - `offset` - the index of the first vertex of the grid
+/// - `indices` - the line list to append to
+fn insert_grid_line_indices(n: u32, spacing: u32, offset: u32, indices: &mut Vec<u32>) {
+    let mut drawn: Vec<u32> = (0..n).step_by(spacing as usize).collect();
+    if drawn.last() != Some(&(n - 1)) {
+        drawn.push(n - 1);
+    }
+
+    for &i in &drawn {
+        for j in 0..n - 1 {
+            indices.extend([offset + i * n + j, offset + i * n + j + 1]);
+        }
+    }
+    for &j in &drawn {
+        for i in 0..n - 1 {
+            indices.extend([offset + i * n + j, offset + (i + 1) * n + j]);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use bevy_cube_sphere::projection::{Everitt, Normalized, Quadrilateralized, Spherified, Tangent};
+use bevy_cube_sphere::{CellId, CubeFace, CubeProjection};
+
+/// cells of every face at a few levels, the first and last of each level included
+fn cells() -> Vec<CellId> {
+    let mut cells = Vec::new();
+    for face in CubeFace::ALL {
+        for level in [0, 1, 2, 5, 17, CellId::MAX_LEVEL - 1, CellId::MAX_LEVEL] {
+            let last = (1_u64 << level) as u32 - 1;
+            for [i, j] in [
+                [0, 0],
+                [last, 0],
+                [0, last],
+                [last, last],
+                [last / 3, last / 2],
+            ] {
+                cells.push(CellId::from_face_ij(face, level, i, j));
+            }
+        }
+    }
+    cells
+}
+
+#[test]
+fn cells_keep_their_face_level_and_index() {
+    for face in CubeFace::ALL {
+        for level in [0, 1, 3, 16, CellId::MAX_LEVEL] {
+            let last = (1_u64 << level) as u32 - 1;
+            for ij in [
+                [0, 0],
+                [last, 0],
+                [0, last],
+                [last, last],
+                [last / 3, last / 7],
+            ] {
+                let cell = CellId::from_face_ij(face, level, ij[0], ij[1]);
+                assert_eq!(cell.face(), face);
+                assert_eq!(cell.level(), level);
+                assert_eq!(cell.ij(), ij);
+                assert_eq!(CellId::from_raw(cell.raw()), Some(cell));
+            }
+        }
+        assert_eq!(CellId::from_face(face), CellId::from_face_ij(face, 0, 0, 0));
+    }
+}
+
+#[test]
+fn children_and_parent_are_inverse() {
+    for cell in cells() {
+        match cell.children() {
+            Some(children) => {
+                let [i, j] = cell.ij();
+                let morton = [[0, 0], [0, 1], [1, 0], [1, 1]];
+                for (child, [di, dj]) in children.into_iter().zip(morton) {
+                    assert_eq!(child.parent(), Some(cell));
+                    assert_eq!(child.level(), cell.level() + 1);
+                    assert_eq!(child.ij(), [2 * i + di, 2 * j + dj]);
+                    assert!(cell.contains(&child));
+                    assert!(!child.contains(&cell));
+                }
+            }
+            None => assert_eq!(cell.level(), CellId::MAX_LEVEL),
+        }
+
+        match cell.parent() {
+            Some(parent) => {
+                assert!(parent.children().unwrap().contains(&cell));
+                assert_eq!(cell.parent_at(parent.level()), parent);
+            }
+            None => assert_eq!(cell.level(), 0),
+        }
+        assert_eq!(cell.parent_at(0), CellId::from_face(cell.face()));
+        assert_eq!(cell.parent_at(cell.level()), cell);
+    }
+}
+
+#[test]
+fn cells_contain_their_descendants_only() {
+    for cell in cells() {
+        assert!(cell.contains(&cell));
+        assert!(CellId::from_face(cell.face()).contains(&cell));
+
+        let mut descendant = cell;
+        while let Some(children) = descendant.children() {
+            descendant = children[3];
+            assert!(cell.contains(&descendant));
+        }
+
+        for neighbor in cell.edge_neighbors() {
+            assert!(!cell.contains(&neighbor) && !neighbor.contains(&cell));
+        }
+        for face in CubeFace::ALL.into_iter().filter(|f| *f != cell.face()) {
+            assert!(!CellId::from_face(face).contains(&cell));
+        }
+    }
+}
+
+#[test]
+fn points_map_to_the_cells_containing_them() {
+    let projections: [&dyn CubeProjection; 5] = [
+        &Normalized,
+        &Spherified,
+        &Tangent,
+        &Everitt,
+        &Quadrilateralized,
+    ];
+    for projection in projections {
+        for face in CubeFace::ALL {
+            for level in [0, 1, 4, 10] {
+                let last = (1_u64 << level) as u32 - 1;
+                for [i, j] in [
+                    [0, 0],
+                    [last, 0],
+                    [0, last],
+                    [last, last],
+                    [last / 2, last / 3],
+                ] {
+                    let cell = CellId::from_face_ij(face, level, i, j);
+                    let center = cell.center(projection);
+                    assert_eq!(CellId::from_point(&center, level, projection), cell);
+
+                    // a deeper cell of the same point lies inside it
+                    let deep = CellId::from_point(&center, level + 8, projection);
+                    assert!(cell.contains(&deep));
+                    assert_eq!(deep.parent_at(level), cell);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn invalid_raw_values_are_rejected() {
+    // no level marker
+    assert_eq!(CellId::from_raw(0), None);
+    // the marker between two levels
+    let face = CellId::from_face(CubeFace::Front).raw();
+    assert_eq!(CellId::from_raw(face >> 1), None);
+    assert_eq!(CellId::from_raw(face | 1 << 3), None);
+    // the faces 6 and 7 do not exist
+    assert_eq!(CellId::from_raw(6 << 61 | 1 << 60), None);
+    assert_eq!(CellId::from_raw(7 << 61 | 1), None);
+    // the lowest marker is the deepest level
+    let deepest = CellId::from_raw(5 << 61 | 1).unwrap();
+    assert_eq!(deepest.face(), CubeFace::Bottom);
+    assert_eq!(deepest.level(), CellId::MAX_LEVEL);
+}
+
+#[test]
+fn deepest_cells_cover_the_whole_face() {
+    let last = (1_u32 << CellId::MAX_LEVEL) - 1;
+    let cell = CellId::from_face_ij(CubeFace::Top, CellId::MAX_LEVEL, last, last);
+    assert_eq!(cell.ij(), [last, last]);
+    assert_eq!(cell.children(), None);
+    assert_eq!(cell.face_bounds()[1], [1., 1.]);
+}
+
+#[test]
+#[should_panic]
+fn levels_below_the_deepest_panic() {
+    CellId::from_face_ij(CubeFace::Front, CellId::MAX_LEVEL + 1, 0, 0);
+}
+
+#[test]
+#[should_panic]
+fn indices_outside_the_level_panic() {
+    CellId::from_face_ij(CubeFace::Front, 3, 8, 0);
+}
+
+#[test]
+#[should_panic]
+fn parents_below_the_cell_panic() {
+    CellId::from_face_ij(CubeFace::Front, 3, 1, 1).parent_at(4);
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use bevy::prelude::Mesh;
+use bevy_cube_sphere::{CubeSphere, CubeSphereError, SphereClip};
+use common::points;
+
+fn clipped(clip: SphereClip) -> Result<Mesh, CubeSphereError> {
+    CubeSphere {
+        resolution: 9,
+        clip: Some(clip),
+        ..Default::default()
+    }
+    .try_mesh()
+}
+
+/// whether a point of the unit sphere is kept by a clip, up to rounding
+fn keeps(clip: &SphereClip, point: &[f32; 3]) -> bool {
+    match *clip {
+        SphereClip::Cap { center, angle } => {
+            let norm = center.iter().map(|c| c * c).sum::<f32>().sqrt();
+            let cos = (0..3).map(|k| point[k] * center[k]).sum::<f32>() / norm;
+            cos >= angle.cos() - 1e-5
+        }
+        SphereClip::LatitudeBand { min, max } => {
+            (min.sin() - 1e-5..=max.sin() + 1e-5).contains(&point[1])
+        }
+    }
+}
+
+#[test]
+fn clips_keep_the_directions_within_their_bounds() {
+    let clips = [
+        SphereClip::hemisphere([0., 1., 0.]),
+        SphereClip::Cap {
+            center: [1., 1., 1.],
+            angle: 0.4,
+        },
+        SphereClip::LatitudeBand {
+            min: -0.3,
+            max: 0.7,
+        },
+    ];
+    for clip in clips {
+        let mesh = clipped(clip).unwrap();
+        let points = points(&mesh);
+        assert!(!points.is_empty());
+        for point in &points {
+            assert!(keeps(&clip, point), "{:?} {:?}", clip, point);
+        }
+    }
+}
+
+#[test]
+fn clips_on_the_bounds_of_their_ranges_are_accepted() {
+    let whole = clipped(SphereClip::LatitudeBand {
+        min: -FRAC_PI_2,
+        max: FRAC_PI_2,
+    })
+    .unwrap();
+    let unclipped = CubeSphere {
+        resolution: 9,
+        ..Default::default()
+    }
+    .try_mesh()
+    .unwrap();
+    assert_eq!(whole.count_vertices(), unclipped.count_vertices());
+
+    let sphere = clipped(SphereClip::Cap {
+        center: [0., 0., 1.],
+        angle: PI,
+    })
+    .unwrap();
+    assert_eq!(sphere.count_vertices(), unclipped.count_vertices());
+
+    // empty, but well formed
+    for clip in [
+        SphereClip::Cap {
+            center: [0., 0., 1.],
+            angle: 0.,
+        },
+        SphereClip::LatitudeBand { min: 0.3, max: 0.3 },
+    ] {
+        let mesh = clipped(clip).unwrap();
+        for point in &points(&mesh) {
+            assert!(keeps(&clip, point), "{:?} {:?}", clip, point);
+        }
+    }
+}
+
+#[test]
+fn clips_out_of_their_ranges_are_rejected() {
+    let clips = [
+        SphereClip::LatitudeBand {
+            min: 0.5,
+            max: -0.5,
+        },
+        SphereClip::LatitudeBand { min: -2., max: 0.5 },
+        SphereClip::LatitudeBand { min: 0., max: 2. },
+        SphereClip::LatitudeBand {
+            min: f32::NAN,
+            max: 0.,
+        },
+        SphereClip::Cap {
+            center: [0., 1., 0.],
+            angle: -0.1,
+        },
+        SphereClip::Cap {
+            center: [0., 1., 0.],
+            angle: 4.,
+        },
+        SphereClip::Cap {
+            center: [0., 1., 0.],
+            angle: f32::NAN,
+        },
+        SphereClip::hemisphere([0., 0., 0.]),
+        SphereClip::hemisphere([f32::INFINITY, 0., 0.]),
+    ];
+    for clip in clips {
+        match clipped(clip) {
+            Err(CubeSphereError::InvalidClip(_)) => {}
+            other => panic!("{:?} gave {:?}", clip, other.map(|_| ())),
+        }
+    }
+}
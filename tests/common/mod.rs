@@ -0,0 +1,205 @@
+//! helpers shared by the integration tests, every test crate uses a part of them
+#![allow(dead_code)]
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::Mesh;
+use bevy::render::mesh::{MeshVertexAttributeId, VertexAttributeValues};
+use bevy_cube_sphere::{CubeSphere, FaceEdge};
+
+pub fn dot(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub fn cross(a: &[f32; 3], b: &[f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub fn sub(a: &[f32; 3], b: &[f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub fn normalize(v: &[f32; 3]) -> [f32; 3] {
+    let norm = dot(v, v).sqrt();
+    v.map(|c| c / norm)
+}
+
+pub fn distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    let d = sub(a, b);
+    dot(&d, &d).sqrt()
+}
+
+pub fn float2(mesh: &Mesh, attribute: impl Into<MeshVertexAttributeId>) -> Vec<[f32; 2]> {
+    match mesh.attribute(attribute) {
+        Some(VertexAttributeValues::Float32x2(values)) => values.clone(),
+        _ => panic!("missing attribute"),
+    }
+}
+
+pub fn float3(mesh: &Mesh, attribute: impl Into<MeshVertexAttributeId>) -> Vec<[f32; 3]> {
+    match mesh.attribute(attribute) {
+        Some(VertexAttributeValues::Float32x3(values)) => values.clone(),
+        _ => panic!("missing attribute"),
+    }
+}
+
+pub fn float4(mesh: &Mesh, attribute: impl Into<MeshVertexAttributeId>) -> Vec<[f32; 4]> {
+    match mesh.attribute(attribute) {
+        Some(VertexAttributeValues::Float32x4(values)) => values.clone(),
+        _ => panic!("missing attribute"),
+    }
+}
+
+pub fn uint(mesh: &Mesh, attribute: impl Into<MeshVertexAttributeId>) -> Vec<u32> {
+    match mesh.attribute(attribute) {
+        Some(VertexAttributeValues::Uint32(values)) => values.clone(),
+        _ => panic!("missing attribute"),
+    }
+}
+
+pub fn points(mesh: &Mesh) -> Vec<[f32; 3]> {
+    float3(mesh, Mesh::ATTRIBUTE_POSITION)
+}
+
+pub fn normals(mesh: &Mesh) -> Vec<[f32; 3]> {
+    float3(mesh, Mesh::ATTRIBUTE_NORMAL)
+}
+
+pub fn tangents(mesh: &Mesh) -> Vec<[f32; 4]> {
+    float4(mesh, Mesh::ATTRIBUTE_TANGENT)
+}
+
+pub fn uvs(mesh: &Mesh) -> Vec<[f32; 2]> {
+    float2(mesh, Mesh::ATTRIBUTE_UV_0)
+}
+
+pub fn indices(mesh: &Mesh) -> Vec<usize> {
+    mesh.indices().expect("missing indices").iter().collect()
+}
+
+/// the triangles of a mesh as their positions, to compare meshes whatever
+/// the order of their vertices
+pub fn triangle_points(mesh: &Mesh) -> Vec<[[u32; 3]; 3]> {
+    let points = points(mesh);
+    indices(mesh)
+        .chunks(3)
+        .map(|t| [0, 1, 2].map(|k| points[t[k]].map(f32::to_bits)))
+        .collect()
+}
+
+/// points spread over the whole sphere, the axes and the diagonals included
+/// ## Arguments
+/// - `steps` - the number of latitudes, there are twice as many longitudes
+pub fn sphere_points(steps: usize) -> Vec<[f32; 3]> {
+    let mut points = Vec::new();
+    for lat in 0..=steps {
+        for lon in 0..2 * steps {
+            let theta = (lat as f32 / steps as f32 - 0.5) * std::f32::consts::PI;
+            let phi = lon as f32 / (2 * steps) as f32 * std::f32::consts::TAU;
+            points.push([
+                theta.cos() * phi.cos(),
+                theta.sin(),
+                theta.cos() * phi.sin(),
+            ]);
+        }
+    }
+    for x in [-1_f32, 0., 1.] {
+        for y in [-1_f32, 0., 1.] {
+            for z in [-1_f32, 0., 1.] {
+                if x != 0. || y != 0. || z != 0. {
+                    points.push(normalize(&[x, y, z]));
+                }
+            }
+        }
+    }
+    points
+}
+
+/// check that the uv of every vertex of the mesh of a sphere maps back to
+/// the direction of the vertex
+/// ## Arguments
+/// - `sphere` - a sphere of radius `1` without elevation
+/// - `tolerance` - the largest distance between a vertex and its uv mapped back
+pub fn assert_uvs_map_back(sphere: &CubeSphere, tolerance: f32) {
+    let mesh = sphere.try_mesh().unwrap();
+    for (pt, uv) in points(&mesh).iter().zip(&uvs(&mesh)) {
+        assert!((0. ..=1.).contains(&uv[0]) && (0. ..=1.).contains(&uv[1]));
+        let back = sphere
+            .uv_to_point(uv)
+            .unwrap_or_else(|| panic!("{:?}: {:?} of {:?} maps to nowhere", sphere, uv, pt));
+        assert!(
+            distance(&back, pt) < tolerance,
+            "{:?}: {:?} -> {:?} -> {:?}",
+            sphere.uv_layout,
+            pt,
+            uv,
+            back
+        );
+    }
+}
+
+/// the edges of a mesh bordering a single triangle, as the positions of
+/// their ends in increasing order
+pub fn boundary_edges(mesh: &Mesh) -> HashSet<[[u32; 3]; 2]> {
+    let points = points(mesh);
+    let mut counts: HashMap<[[u32; 3]; 2], usize> = HashMap::new();
+    for triangle in indices(mesh).chunks(3) {
+        for k in 0..3 {
+            let mut edge =
+                [triangle[k], triangle[(k + 1) % 3]].map(|v| points[v].map(f32::to_bits));
+            edge.sort();
+            *counts.entry(edge).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count == 1)
+        .map(|(edge, _)| edge)
+        .collect()
+}
+
+/// the vertices along an edge of a patch, in the order of the grid
+/// ## Arguments
+/// - `n` - the number of vertices on the edge of the patch
+/// - `edge` - the edge of the patch
+pub fn patch_edge_vertices(n: usize, edge: FaceEdge) -> Vec<usize> {
+    match edge {
+        FaceEdge::MinU => (0..n).collect(),
+        FaceEdge::MaxU => (0..n).map(|j| (n - 1) * n + j).collect(),
+        FaceEdge::MinV => (0..n).map(|i| i * n).collect(),
+        FaceEdge::MaxV => (0..n).map(|i| i * n + n - 1).collect(),
+    }
+}
+
+/// check that the triangles of a patch along one of its edges meet the
+/// triangles of a neighboring patch without a crack
+/// ## Arguments
+/// - `patch`, `n` - the mesh of the patch and the number of vertices on its edge
+/// - `edge` - the edge of the patch bordering the neighbor
+/// - `neighbor` - the mesh of the neighboring patch
+pub fn assert_patch_edge_is_shared(patch: &Mesh, n: usize, edge: FaceEdge, neighbor: &Mesh) {
+    let points = points(patch);
+    let along: HashSet<[u32; 3]> = patch_edge_vertices(n, edge)
+        .into_iter()
+        .map(|v| points[v].map(f32::to_bits))
+        .collect();
+    let neighbor_edges = boundary_edges(neighbor);
+
+    let mut shared = 0;
+    for segment in boundary_edges(patch) {
+        if segment.iter().all(|end| along.contains(end)) {
+            assert!(
+                neighbor_edges.contains(&segment),
+                "{:?}: {:?}",
+                edge,
+                segment
+            );
+            shared += 1;
+        }
+    }
+    assert!(shared > 0);
+}
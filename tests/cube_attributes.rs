@@ -0,0 +1,38 @@
+mod common;
+
+use std::sync::Arc;
+
+use bevy_cube_sphere::uv::{DiscPacking, FaceTiles};
+use bevy_cube_sphere::{CubeFace, CubeSphere, UvLayout, ATTRIBUTE_CUBE_FACE, ATTRIBUTE_CUBE_POINT};
+use common::{dot, float3, indices, uint};
+
+#[test]
+fn welded_triangles_lie_on_a_single_cube_face() {
+    let n = 8;
+    let layouts: [Arc<dyn UvLayout>; 2] = [Arc::new(DiscPacking), Arc::new(FaceTiles::cross(0.))];
+    for uv_layout in layouts {
+        let mesh = CubeSphere {
+            resolution: n,
+            welded: true,
+            cube_attributes: true,
+            uv_layout: uv_layout.clone(),
+            ..Default::default()
+        }
+        .try_mesh()
+        .unwrap();
+
+        // the face differs between the copies of a border vertex, none is shared
+        assert_eq!(mesh.count_vertices(), 6 * n * n, "{:?}", uv_layout);
+
+        let faces = uint(&mesh, ATTRIBUTE_CUBE_FACE);
+        let cube_points = float3(&mesh, ATTRIBUTE_CUBE_POINT);
+        for triangle in indices(&mesh).chunks(3) {
+            let face = faces[triangle[0]];
+            for v in triangle {
+                assert_eq!(faces[*v], face, "{:?}", uv_layout);
+                let normal = CubeFace::ALL[face as usize].normal();
+                assert!((dot(&cube_points[*v], &normal) - 1.).abs() < 1e-6);
+            }
+        }
+    }
+}
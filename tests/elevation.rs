@@ -0,0 +1,55 @@
+mod common;
+
+use std::sync::Arc;
+
+use bevy_cube_sphere::CubeSphere;
+use common::{distance, dot, normalize, normals, points};
+
+/// the slope of the elevation `h(d) = SLOPE * d_y`
+const SLOPE: f32 = 0.2;
+
+fn sphere(radius: f32) -> CubeSphere {
+    CubeSphere {
+        resolution: 32,
+        radius,
+        elevation: Some(Arc::new(|dir: &[f32; 3]| SLOPE * dir[1])),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn vertices_are_displaced_along_their_direction() {
+    for radius in [1., 3.] {
+        let mesh = sphere(radius).try_mesh().unwrap();
+        for point in points(&mesh) {
+            let dir = normalize(&point);
+            let length = dot(&point, &point).sqrt();
+            assert!(
+                (length - (radius + SLOPE * dir[1])).abs() < 1e-5,
+                "{:?}",
+                point
+            );
+        }
+    }
+}
+
+#[test]
+fn normals_follow_the_displaced_surface() {
+    for radius in [1., 3.] {
+        let mesh = sphere(radius).try_mesh().unwrap();
+        for (point, normal) in points(&mesh).iter().zip(normals(&mesh)) {
+            // the gradient of `|p| - radius - SLOPE * p_y / |p|`
+            let d = normalize(point);
+            let expected = normalize(&[0, 1, 2].map(|k| {
+                let e_y = (k == 1) as u8 as f32;
+                (radius + SLOPE * d[1]) * d[k] - SLOPE * (e_y - d[1] * d[k])
+            }));
+            assert!(
+                distance(&normal, &expected) < 1e-3,
+                "{:?} {:?}",
+                normal,
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use bevy_cube_sphere::{CubeSphere, WGS84_FLATTENING};
+use common::{dot, normalize, normals, points, tangents};
+
+fn ellipsoids() -> Vec<CubeSphere> {
+    vec![
+        CubeSphere {
+            radii: Some([2., 1., 0.5]),
+            resolution: 12,
+            ..Default::default()
+        },
+        CubeSphere::oblate(1., 0.3),
+        CubeSphere::oblate(6_378.137, WGS84_FLATTENING),
+    ]
+}
+
+#[test]
+fn oblate_spheroids_are_flattened_along_y() {
+    let sphere = CubeSphere::oblate(6_378.137, WGS84_FLATTENING);
+    let [a, b, c] = sphere.radii.unwrap();
+    assert_eq!(a, 6_378.137);
+    assert_eq!(c, a);
+    assert!((b - 6_356.752).abs() < 1e-2, "{}", b);
+}
+
+#[test]
+fn vertices_lie_on_the_ellipsoid() {
+    for sphere in ellipsoids() {
+        let radii = sphere.radii.unwrap();
+        for pt in points(&sphere.try_mesh().unwrap()) {
+            let scaled = [0, 1, 2].map(|k| pt[k] / radii[k]);
+            assert!(
+                (dot(&scaled, &scaled).sqrt() - 1.).abs() < 1e-5,
+                "{:?} {:?}",
+                radii,
+                pt
+            );
+        }
+    }
+}
+
+#[test]
+fn normals_are_the_gradient_of_the_ellipsoid() {
+    for sphere in ellipsoids() {
+        let radii = sphere.radii.unwrap();
+        let mesh = sphere.try_mesh().unwrap();
+
+        let mut off_radial = 0;
+        for ((pt, normal), tangent) in points(&mesh)
+            .iter()
+            .zip(&normals(&mesh))
+            .zip(&tangents(&mesh))
+        {
+            // the gradient of `(x / a)² + (y / b)² + (z / c)²`
+            let gradient = normalize(&[0, 1, 2].map(|k| pt[k] / radii[k].powi(2)));
+            assert!(
+                dot(normal, &gradient) > 1. - 1e-5,
+                "{:?} {:?}",
+                normal,
+                gradient
+            );
+            assert!(dot(&[tangent[0], tangent[1], tangent[2]], normal).abs() < 1e-5);
+
+            if dot(normal, &normalize(pt)) < 0.99 {
+                off_radial += 1;
+            }
+        }
+        // the normals tilt away from the center except on the axes
+        assert_eq!(off_radial > 0, radii[1] / radii[0] < 0.9, "{:?}", radii);
+    }
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bevy::prelude::Mesh;
+use bevy_cube_sphere::uv::Equirectangular;
+use bevy_cube_sphere::CubeSphere;
+use common::{distance, indices, points, uvs};
+
+/// an odd resolution puts a vertex on both poles
+fn equirect(resolution: usize) -> CubeSphere {
+    CubeSphere {
+        resolution,
+        uv_layout: Arc::new(Equirectangular),
+        ..Default::default()
+    }
+}
+
+fn points_and_uvs(mesh: &Mesh) -> (Vec<[f32; 3]>, Vec<[f32; 2]>) {
+    (points(mesh), uvs(mesh))
+}
+
+fn is_pole(pt: &[f32; 3]) -> bool {
+    pt[1].abs() > 1. - 1e-6
+}
+
+#[test]
+fn the_antimeridian_is_split_into_both_sides() {
+    for resolution in [8, 9, 17] {
+        let sphere = equirect(resolution);
+        let (points, uvs) = points_and_uvs(&sphere.try_mesh().unwrap());
+
+        // the `u` of the copies of every vertex on the antimeridian
+        let mut sides: HashMap<[u32; 3], Vec<f32>> = HashMap::new();
+        for (pt, uv) in points.iter().zip(&uvs) {
+            if pt[0] == 0. && pt[2] < 0. && !is_pole(pt) {
+                sides.entry(pt.map(f32::to_bits)).or_default().push(uv[0]);
+            }
+        }
+        assert!(!sides.is_empty());
+        for (pt, us) in sides {
+            assert!(us.contains(&0.) && us.contains(&1.), "{:?} {:?}", pt, us);
+            assert!(us.iter().all(|u| *u == 0. || *u == 1.));
+        }
+
+        // a seam vertex maps back from both sides
+        for v in [0.1, 0.3, 0.5, 0.7, 0.9] {
+            let west = sphere.uv_to_point(&[0., v]).unwrap();
+            let east = sphere.uv_to_point(&[1., v]).unwrap();
+            assert!(distance(&west, &east) < 1e-6, "{:?} {:?}", west, east);
+            assert!(west[2] < 0. && west[0].abs() < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn no_triangle_wraps_around_the_texture() {
+    for resolution in [8, 9, 17] {
+        let mesh = equirect(resolution).try_mesh().unwrap();
+        let (_, uvs) = points_and_uvs(&mesh);
+        for triangle in indices(&mesh).chunks(3) {
+            let us = triangle.iter().map(|v| uvs[*v][0]);
+            let span = us.clone().fold(f32::MIN, f32::max) - us.fold(f32::MAX, f32::min);
+            assert!(span < 0.5, "{:?}", triangle);
+        }
+    }
+}
+
+#[test]
+fn the_poles_take_the_first_and_last_rows() {
+    let sphere = equirect(9);
+    let (points, uvs) = points_and_uvs(&sphere.try_mesh().unwrap());
+
+    // a pole vertex gets the `u` of each of its triangles
+    let mut poles = [Vec::new(), Vec::new()];
+    for (pt, uv) in points.iter().zip(&uvs) {
+        if is_pole(pt) {
+            assert_eq!(uv[1], if pt[1] > 0. { 0. } else { 1. }, "{:?}", pt);
+            poles[usize::from(pt[1] < 0.)].push(uv[0]);
+        }
+    }
+    for us in poles {
+        assert!(us.len() > 1, "{:?}", us);
+    }
+
+    // every `u` of the first and last rows maps to the poles
+    for u in [0., 0.25, 0.5, 0.75, 1.] {
+        let north = sphere.uv_to_point(&[u, 0.]).unwrap();
+        let south = sphere.uv_to_point(&[u, 1.]).unwrap();
+        assert!(distance(&north, &[0., 1., 0.]) < 1e-6, "{:?}", north);
+        assert!(distance(&south, &[0., -1., 0.]) < 1e-6, "{:?}", south);
+    }
+    for pole in [[0., 1., 0.], [0., -1., 0.]] {
+        for uv in sphere.point_to_uvs(&pole) {
+            assert_eq!(uv[1], if pole[1] > 0. { 0. } else { 1. });
+            let back = sphere.uv_to_point(&uv).unwrap();
+            assert!(distance(&back, &pole) < 1e-6);
+        }
+    }
+}
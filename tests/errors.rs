@@ -0,0 +1,241 @@
+use std::sync::Arc;
+
+use bevy::render::mesh::MeshVertexAttribute;
+use bevy::render::render_resource::VertexFormat;
+use bevy_cube_sphere::uv::FaceTiles;
+use bevy_cube_sphere::{
+    AttributeHook, AttributeValue, CellId, CubeFace, CubeSphere, CubeSphereError, GridLines,
+    LodQuadtree, LodSeams, LodSettings, PatchSeam, SphereClip, VertexInfo,
+};
+
+fn sphere(resolution: usize) -> CubeSphere {
+    CubeSphere {
+        resolution,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn resolutions_below_two_are_rejected() {
+    for resolution in [0, 1] {
+        assert_eq!(
+            sphere(resolution).try_mesh().map(|_| ()),
+            Err(CubeSphereError::InvalidResolution(resolution))
+        );
+        assert_eq!(
+            sphere(8)
+                .try_patch_mesh(
+                    CellId::from_face(CubeFace::Top),
+                    resolution,
+                    PatchSeam::None
+                )
+                .map(|_| ()),
+            Err(CubeSphereError::InvalidResolution(resolution))
+        );
+    }
+}
+
+#[test]
+fn radii_not_finite_and_positive_are_rejected() {
+    for radius in [0., -1., f32::INFINITY] {
+        let sphere = CubeSphere {
+            radius,
+            ..Default::default()
+        };
+        assert_eq!(
+            sphere.try_mesh().map(|_| ()),
+            Err(CubeSphereError::InvalidRadius(radius))
+        );
+
+        let ellipsoid = CubeSphere {
+            radii: Some([1., radius, 1.]),
+            ..Default::default()
+        };
+        assert_eq!(
+            ellipsoid.try_mesh().map(|_| ()),
+            Err(CubeSphereError::InvalidRadius(radius))
+        );
+    }
+
+    let sphere = CubeSphere {
+        radius: f32::NAN,
+        ..Default::default()
+    };
+    assert!(matches!(
+        sphere.try_mesh(),
+        Err(CubeSphereError::InvalidRadius(r)) if r.is_nan()
+    ));
+}
+
+#[test]
+fn resolutions_past_the_32_bit_indices_are_rejected() {
+    // `6 * resolution²` overflows `u32` first, then `usize`
+    for resolution in [26_755, 1 << 20, usize::MAX] {
+        let error = sphere(resolution).try_mesh().unwrap_err();
+        assert_eq!(error, CubeSphereError::TooManyVertices(resolution));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Cannot create a cube sphere with resolution {}. (Limited to {} vertices)",
+                resolution,
+                u32::MAX
+            )
+        );
+    }
+}
+
+#[test]
+fn vertex_budgets_below_a_triangle_are_rejected() {
+    for max_vertices in [0, 1, 2] {
+        assert_eq!(
+            sphere(8).try_meshes(max_vertices).map(|_| ()),
+            Err(CubeSphereError::InvalidVertexBudget(max_vertices))
+        );
+    }
+}
+
+#[test]
+fn levels_past_the_deepest_are_rejected() {
+    let settings = LodSettings {
+        max_level: CellId::MAX_LEVEL + 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        LodQuadtree::new(CubeSphere::default(), settings).map(|_| ()),
+        Err(CubeSphereError::InvalidLevel(CellId::MAX_LEVEL + 1))
+    );
+}
+
+#[test]
+fn stitched_patches_of_even_resolution_are_rejected() {
+    assert_eq!(
+        sphere(8)
+            .try_patch_mesh(CellId::from_face(CubeFace::Top), 8, PatchSeam::Stitch(1))
+            .map(|_| ()),
+        Err(CubeSphereError::InvalidStitchResolution(8))
+    );
+
+    let settings = LodSettings {
+        patch_resolution: 8,
+        seams: LodSeams::Stitch,
+        ..Default::default()
+    };
+    assert_eq!(
+        LodQuadtree::new(CubeSphere::default(), settings).map(|_| ()),
+        Err(CubeSphereError::InvalidStitchResolution(8))
+    );
+}
+
+#[test]
+fn meshes_without_triangles_are_incompatible() {
+    let sphere = sphere(8);
+    let mut mesh = sphere.try_mesh().unwrap();
+    mesh.set_indices(None);
+    assert_eq!(
+        sphere.recompute_normals(&mut mesh),
+        Err(CubeSphereError::IncompatibleMesh(6 * 8 * 8))
+    );
+}
+
+#[test]
+fn clips_out_of_their_ranges_are_rejected() {
+    let clip = SphereClip::LatitudeBand { min: 1., max: -1. };
+    let sphere = CubeSphere {
+        clip: Some(clip),
+        ..Default::default()
+    };
+    assert_eq!(
+        sphere.try_mesh().map(|_| ()),
+        Err(CubeSphereError::InvalidClip(clip))
+    );
+    assert_eq!(
+        sphere.try_meshes(100).map(|_| ()),
+        Err(CubeSphereError::InvalidClip(clip))
+    );
+}
+
+#[test]
+fn zero_line_spacings_are_rejected() {
+    assert_eq!(
+        sphere(8)
+            .try_wireframe_mesh(GridLines::Every(0))
+            .map(|_| ()),
+        Err(CubeSphereError::InvalidLineSpacing(0))
+    );
+}
+
+/// writes a value of the format of its attribute, or of another one
+struct Hook {
+    attribute: MeshVertexAttribute,
+    value: AttributeValue,
+}
+
+impl AttributeHook for Hook {
+    fn attributes(&self) -> Vec<MeshVertexAttribute> {
+        vec![self.attribute.clone()]
+    }
+
+    fn compute(&self, _: &VertexInfo, values: &mut [AttributeValue]) {
+        values[0] = self.value;
+    }
+}
+
+#[test]
+fn attributes_not_matching_their_values_are_rejected() {
+    let hooks = [
+        // not a 32 bit format
+        Hook {
+            attribute: MeshVertexAttribute::new("Vertex_Packed", 7_001, VertexFormat::Uint16x4),
+            value: AttributeValue::Uint32(0),
+        },
+        // a value of another format
+        Hook {
+            attribute: MeshVertexAttribute::new("Vertex_Packed", 7_002, VertexFormat::Float32),
+            value: AttributeValue::Uint32(0),
+        },
+    ];
+    for hook in hooks {
+        let sphere = CubeSphere {
+            attribute_hook: Some(Arc::new(hook)),
+            ..Default::default()
+        };
+        assert_eq!(
+            sphere.try_mesh().map(|_| ()),
+            Err(CubeSphereError::InvalidAttribute("Vertex_Packed"))
+        );
+    }
+}
+
+#[test]
+fn gutters_out_of_range_are_rejected() {
+    let sphere = CubeSphere {
+        uv_layout: Arc::new(FaceTiles::atlas(0.5)),
+        ..Default::default()
+    };
+    assert_eq!(
+        sphere.try_mesh().map(|_| ()),
+        Err(CubeSphereError::InvalidGutter(0.5))
+    );
+}
+
+#[test]
+fn skirts_of_negative_or_infinite_depth_are_rejected() {
+    for depth in [-0.1, f32::INFINITY] {
+        let cell = CellId::from_face(CubeFace::Top);
+        assert_eq!(
+            sphere(8)
+                .try_patch_mesh(cell, 9, PatchSeam::Skirt(depth))
+                .map(|_| ()),
+            Err(CubeSphereError::InvalidSkirtDepth(depth))
+        );
+
+        let settings = LodSettings {
+            seams: LodSeams::Skirt(depth),
+            ..Default::default()
+        };
+        assert_eq!(
+            LodQuadtree::new(CubeSphere::default(), settings).map(|_| ()),
+            Err(CubeSphereError::InvalidSkirtDepth(depth))
+        );
+    }
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use std::sync::Arc;
+
+use bevy_cube_sphere::projection::{Everitt, Normalized, Quadrilateralized, Spherified, Tangent};
+use bevy_cube_sphere::{CubeFace, CubeProjection, CubeSphere};
+use common::distance;
+
+fn spheres() -> Vec<CubeSphere> {
+    let projections: [Arc<dyn CubeProjection>; 5] = [
+        Arc::new(Normalized),
+        Arc::new(Spherified),
+        Arc::new(Tangent),
+        Arc::new(Everitt),
+        Arc::new(Quadrilateralized),
+    ];
+    projections
+        .into_iter()
+        .map(|projection| CubeSphere {
+            projection,
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// face coordinates on a grid, the edges and corners of the face included
+fn face_coords() -> Vec<[f32; 2]> {
+    const STEPS: i32 = 16;
+    let mut coords = Vec::new();
+    for i in 0..=STEPS {
+        for j in 0..=STEPS {
+            coords.push([i, j].map(|k| 2. * k as f32 / STEPS as f32 - 1.));
+        }
+    }
+    coords
+}
+
+#[test]
+fn face_coords_round_trip_through_the_sphere() {
+    for sphere in spheres() {
+        for face in CubeFace::ALL {
+            for coords in face_coords() {
+                let dir = sphere.face_coords_to_sphere(face, coords);
+                assert!((distance(&dir, &[0.; 3]) - 1.).abs() < 1e-5);
+
+                let (back_face, back) = sphere.sphere_to_face_coords(&dir).unwrap();
+                assert!(back.iter().all(|c| c.abs() <= 1. + 1e-6));
+
+                // a point on an edge or a corner may come back on any of its faces
+                let on_border = coords.iter().any(|c| c.abs() == 1.);
+                if !on_border {
+                    assert_eq!(back_face, face, "{:?} {:?}", sphere.projection, coords);
+                    assert!(
+                        (back[0] - coords[0]).abs() < 1e-4 && (back[1] - coords[1]).abs() < 1e-4,
+                        "{:?} {:?} {:?} -> {:?}",
+                        sphere.projection,
+                        face,
+                        coords,
+                        back
+                    );
+                }
+                let again = sphere.face_coords_to_sphere(back_face, back);
+                assert!(
+                    distance(&again, &dir) < 1e-4,
+                    "{:?} {:?} {:?} -> {:?} {:?}",
+                    sphere.projection,
+                    face,
+                    coords,
+                    back_face,
+                    back
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn edges_and_corners_come_back_on_a_face_meeting_there() {
+    let sphere = CubeSphere::default();
+    for face in CubeFace::ALL {
+        for coords in face_coords()
+            .into_iter()
+            .filter(|c| c.iter().any(|c| c.abs() == 1.))
+        {
+            let dir = sphere.face_coords_to_sphere(face, coords);
+            let (back_face, back) = sphere.sphere_to_face_coords(&dir).unwrap();
+            if back_face != face {
+                // the face it comes back on meets the edge along one of its borders
+                assert!(
+                    back.iter().any(|c| (c.abs() - 1.).abs() < 1e-5),
+                    "{:?}",
+                    back
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn directions_of_any_length_give_the_same_coordinates() {
+    let sphere = CubeSphere::default();
+    let dir = sphere.face_coords_to_sphere(CubeFace::Front, [0.3, -0.6]);
+    let (face, coords) = sphere.sphere_to_face_coords(&dir).unwrap();
+    for scale in [1e-3, 2., 1e4] {
+        let (scaled_face, scaled) = sphere
+            .sphere_to_face_coords(&dir.map(|c| c * scale))
+            .unwrap();
+        assert_eq!(scaled_face, face);
+        assert!((scaled[0] - coords[0]).abs() < 1e-5 && (scaled[1] - coords[1]).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn zero_and_non_finite_directions_have_no_face() {
+    let sphere = CubeSphere::default();
+    for dir in [[0., 0., 0.], [f32::NAN, 0., 1.], [f32::INFINITY, 0., 0.]] {
+        assert_eq!(sphere.sphere_to_face_coords(&dir), None);
+    }
+}
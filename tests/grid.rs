@@ -0,0 +1,188 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy_cube_sphere::{CubeFace, GridCoord};
+
+const RESOLUTIONS: [usize; 4] = [2, 3, 6, 9];
+
+/// the vertex on the cube scaled so that the faces are at `±(resolution - 1)`
+fn lattice_point(vertex: GridCoord, resolution: usize) -> [i64; 3] {
+    let m = resolution as f32 - 1.;
+    let normal = vertex.face.normal();
+    let [u, v] = vertex.face.axes();
+    let a = 2. * vertex.i as f32 - m;
+    let b = 2. * vertex.j as f32 - m;
+    [0, 1, 2].map(|k| (m * normal[k] + a * u[k] + b * v[k]).round() as i64)
+}
+
+/// the corners of a cell on the cube
+fn cell_corners(cell: GridCoord, resolution: usize) -> HashSet<[i64; 3]> {
+    [[0, 0], [1, 0], [0, 1], [1, 1]]
+        .into_iter()
+        .map(|[di, dj]| {
+            let corner = GridCoord::new(cell.face, cell.i + di, cell.j + dj);
+            lattice_point(corner, resolution)
+        })
+        .collect()
+}
+
+fn cells(resolution: usize) -> Vec<GridCoord> {
+    let m = resolution as u32 - 1;
+    CubeFace::ALL
+        .into_iter()
+        .flat_map(|face| (0..m).flat_map(move |i| (0..m).map(move |j| GridCoord::new(face, i, j))))
+        .collect()
+}
+
+fn vertices(resolution: usize) -> Vec<GridCoord> {
+    let n = resolution as u32;
+    CubeFace::ALL
+        .into_iter()
+        .flat_map(|face| (0..n).flat_map(move |i| (0..n).map(move |j| GridCoord::new(face, i, j))))
+        .collect()
+}
+
+#[test]
+fn cell_neighbors_share_an_edge_or_a_corner() {
+    for resolution in RESOLUTIONS {
+        let all = cells(resolution);
+        for cell in &all {
+            let corners = cell_corners(*cell, resolution);
+            let shared = |other: &GridCoord| {
+                corners
+                    .intersection(&cell_corners(*other, resolution))
+                    .count()
+            };
+
+            for neighbor in cell.cell_neighbors4(resolution) {
+                assert_eq!(shared(&neighbor), 2, "{:?} {:?}", cell, neighbor);
+            }
+
+            // every cell touching this one, across the edges and corners of the cube
+            let touching: HashSet<GridCoord> = all
+                .iter()
+                .filter(|other| *other != cell && shared(other) > 0)
+                .copied()
+                .collect();
+            let neighbors8 = cell.cell_neighbors8(resolution);
+            assert_eq!(neighbors8.len(), touching.len());
+            assert_eq!(neighbors8.into_iter().collect::<HashSet<_>>(), touching);
+        }
+    }
+}
+
+#[test]
+fn cell_neighbors_are_symmetric() {
+    for resolution in RESOLUTIONS {
+        for cell in cells(resolution) {
+            for neighbor in cell.cell_neighbors4(resolution) {
+                assert!(neighbor.cell_neighbors4(resolution).contains(&cell));
+            }
+            for neighbor in cell.cell_neighbors8(resolution) {
+                assert!(neighbor.cell_neighbors8(resolution).contains(&cell));
+            }
+        }
+    }
+}
+
+#[test]
+fn cells_wrap_across_the_12_edges_and_8_corners() {
+    for resolution in RESOLUTIONS {
+        let m = resolution - 1;
+        let all = cells(resolution);
+
+        // each of the 12 edges is crossed by `m` pairs of cells, both ways
+        let crossings = all
+            .iter()
+            .flat_map(|cell| {
+                cell.cell_neighbors4(resolution)
+                    .into_iter()
+                    .filter(move |n| n.face != cell.face)
+            })
+            .count();
+        assert_eq!(crossings, 12 * m * 2);
+
+        let mut face_pairs = HashSet::new();
+        for cell in &all {
+            for neighbor in cell.cell_neighbors4(resolution) {
+                if neighbor.face != cell.face {
+                    let (a, b) = (cell.face.index(), neighbor.face.index());
+                    face_pairs.insert((a.min(b), a.max(b)));
+                }
+            }
+        }
+        assert_eq!(face_pairs.len(), 12);
+
+        // the 3 cells around each corner of the cube have 7 neighbors
+        let corner_cells = all
+            .iter()
+            .filter(|cell| cell.cell_neighbors8(resolution).len() == 7)
+            .count();
+        if m >= 2 {
+            assert_eq!(corner_cells, 8 * 3);
+        } else {
+            // a single cell per face touches all 4 corners of its face
+            assert_eq!(corner_cells, 0);
+        }
+    }
+}
+
+#[test]
+fn vertex_aliases_cover_every_face_of_the_vertex() {
+    for resolution in RESOLUTIONS {
+        let mut faces_of: HashMap<[i64; 3], HashSet<GridCoord>> = HashMap::new();
+        for vertex in vertices(resolution) {
+            faces_of
+                .entry(lattice_point(vertex, resolution))
+                .or_default()
+                .insert(vertex);
+        }
+
+        let mut corners = 0;
+        for vertex in vertices(resolution) {
+            let aliases = vertex.vertex_aliases(resolution);
+            assert_eq!(aliases[0], vertex);
+            let expected = &faces_of[&lattice_point(vertex, resolution)];
+            assert_eq!(aliases.len(), expected.len());
+            assert_eq!(&aliases.iter().copied().collect::<HashSet<_>>(), expected);
+            if aliases.len() == 3 {
+                corners += 1;
+            }
+        }
+        // every corner of the cube is addressed from its 3 faces
+        assert_eq!(corners, 8 * 3);
+        assert_eq!(faces_of.values().filter(|f| f.len() == 3).count(), 8);
+        assert_eq!(
+            faces_of.values().filter(|f| f.len() == 2).count(),
+            12 * (resolution - 2)
+        );
+    }
+}
+
+#[test]
+fn vertex_neighbors_are_one_step_away_and_symmetric() {
+    for resolution in RESOLUTIONS {
+        for vertex in vertices(resolution) {
+            let point = lattice_point(vertex, resolution);
+            let neighbors4 = vertex.vertex_neighbors4(resolution);
+            let corner = vertex.vertex_aliases(resolution).len() == 3;
+            assert_eq!(neighbors4.len(), if corner { 3 } else { 4 });
+
+            for neighbor in neighbors4 {
+                let other = lattice_point(neighbor, resolution);
+                let distance: i64 = (0..3).map(|k| (point[k] - other[k]).abs()).sum();
+                assert_eq!(distance, 2);
+
+                // the way back may start from another alias of the vertex
+                assert!(neighbor
+                    .vertex_neighbors4(resolution)
+                    .iter()
+                    .any(|back| lattice_point(*back, resolution) == point));
+            }
+
+            if resolution >= 3 {
+                let neighbors8 = vertex.vertex_neighbors8(resolution);
+                assert_eq!(neighbors8.len(), if corner { 6 } else { 8 });
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use std::sync::Arc;
+
+use bevy::prelude::Mesh;
+use bevy::render::mesh::MeshVertexAttribute;
+use bevy::render::render_resource::VertexFormat;
+use bevy_cube_sphere::{AttributeHook, AttributeValue, CubeFace, CubeSphere, VertexInfo};
+use common::{float4, normalize, points, uint};
+
+const ATTRIBUTE_BIOME: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_Biome", 7_101, VertexFormat::Uint32);
+
+/// colors every vertex with its direction, and tags it with a biome
+struct Biomes {
+    /// whether the biome is the face of the vertex, or the hemisphere it is in
+    per_face: bool,
+}
+
+impl AttributeHook for Biomes {
+    fn attributes(&self) -> Vec<MeshVertexAttribute> {
+        vec![Mesh::ATTRIBUTE_COLOR, ATTRIBUTE_BIOME]
+    }
+
+    fn compute(&self, vertex: &VertexInfo, values: &mut [AttributeValue]) {
+        let [x, y, z] = vertex.sphere_point;
+        values[0] = AttributeValue::Float32x4([x, y, z, 1.]);
+        values[1] = AttributeValue::Uint32(if self.per_face {
+            vertex.face.index() as u32
+        } else {
+            (y >= 0.) as u32
+        });
+    }
+}
+
+fn sphere(n: usize, per_face: bool, welded: bool) -> CubeSphere {
+    CubeSphere {
+        resolution: n,
+        welded,
+        attribute_hook: Some(Arc::new(Biomes { per_face })),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn hook_values_are_written_for_every_vertex() {
+    let n = 8;
+    let mesh = sphere(n, true, false).try_mesh().unwrap();
+    let colors = float4(&mesh, Mesh::ATTRIBUTE_COLOR);
+    let biomes = uint(&mesh, ATTRIBUTE_BIOME);
+
+    assert_eq!(colors.len(), 6 * n * n);
+    assert_eq!(biomes.len(), 6 * n * n);
+    for (point, color) in points(&mesh).iter().zip(&colors) {
+        let dir = normalize(point);
+        for k in 0..3 {
+            assert!((dir[k] - color[k]).abs() < 1e-5, "{:?} {:?}", point, color);
+        }
+        assert_eq!(color[3], 1.);
+    }
+    // the faces are emitted in the order of `CubeFace::ALL`
+    for (face, chunk) in CubeFace::ALL.iter().zip(biomes.chunks(n * n)) {
+        assert!(chunk.iter().all(|b| *b == face.index() as u32));
+    }
+}
+
+#[test]
+fn welded_meshes_keep_the_copies_with_different_hook_values() {
+    let n = 8;
+
+    // the biome of a border vertex differs per face, every copy stays
+    let mesh = sphere(n, true, true).try_mesh().unwrap();
+    assert_eq!(mesh.count_vertices(), 6 * n * n);
+
+    // the values only depend on the direction, the copies become one
+    let mesh = sphere(n, false, true).try_mesh().unwrap();
+    assert_eq!(mesh.count_vertices(), 6 * n * n - 12 * n + 8);
+    let colors = float4(&mesh, Mesh::ATTRIBUTE_COLOR);
+    for (point, color) in points(&mesh).iter().zip(&colors) {
+        let dir = normalize(point);
+        for k in 0..3 {
+            assert!((dir[k] - color[k]).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use bevy_cube_sphere::{CubeFace, CubeSphere};
+use common::{cross, dot, indices, normalize, normals, points, sub, uvs};
+
+const N: usize = 9;
+
+fn sphere(inverted: bool) -> CubeSphere {
+    CubeSphere {
+        resolution: N,
+        inverted,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn inverted_triangles_face_the_center() {
+    for inverted in [false, true] {
+        let mesh = sphere(inverted).try_mesh().unwrap();
+        let points = points(&mesh);
+        for triangle in indices(&mesh).chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|k| points[triangle[k]]);
+            let normal = cross(&sub(&b, &a), &sub(&c, &a));
+            let centroid = [0, 1, 2].map(|k| (a[k] + b[k] + c[k]) / 3.);
+            assert_eq!(dot(&normal, &centroid) < 0., inverted, "{:?}", triangle);
+        }
+    }
+}
+
+#[test]
+fn inverted_normals_point_to_the_center() {
+    let mesh = sphere(true).try_mesh().unwrap();
+    for (pt, normal) in points(&mesh).iter().zip(&normals(&mesh)) {
+        let inward = normalize(pt).map(|c| -c);
+        assert!(dot(normal, &inward) > 1. - 1e-6, "{:?} {:?}", pt, normal);
+    }
+}
+
+#[test]
+fn inverted_uvs_mirror_the_outside_ones_within_every_face() {
+    let outside = sphere(false).try_mesh().unwrap();
+    let inside = sphere(true).try_mesh().unwrap();
+    let (outside_points, outside_uvs) = (points(&outside), uvs(&outside));
+    let (inside_points, inside_uvs) = (points(&inside), uvs(&inside));
+    assert_eq!(outside_points, inside_points);
+
+    // the vertex `(i, j)` seen from the inside shows the texture of the
+    // vertex `(N - 1 - i, j)` seen from the outside
+    for face in CubeFace::ALL {
+        let first = face.index() * N * N;
+        for i in 0..N {
+            for j in 0..N {
+                let inside_uv = inside_uvs[first + i * N + j];
+                let outside_uv = outside_uvs[first + (N - 1 - i) * N + j];
+                assert!(
+                    (inside_uv[0] - outside_uv[0]).abs() < 1e-6
+                        && (inside_uv[1] - outside_uv[1]).abs() < 1e-6,
+                    "{:?} ({}, {}): {:?} {:?}",
+                    face,
+                    i,
+                    j,
+                    inside_uv,
+                    outside_uv
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use std::sync::Arc;
+
+use bevy::prelude::Mesh;
+use bevy_cube_sphere::uv::FaceLayers;
+use bevy_cube_sphere::{CubeFace, CubeSphere, ATTRIBUTE_CUBE_FACE};
+use common::{distance, points, uint, uvs};
+
+fn layered(inverted: bool) -> CubeSphere {
+    CubeSphere {
+        resolution: 5,
+        uv_layout: Arc::new(FaceLayers),
+        inverted,
+        ..Default::default()
+    }
+}
+
+fn faces(mesh: &Mesh) -> Vec<u32> {
+    uint(mesh, ATTRIBUTE_CUBE_FACE)
+}
+
+#[test]
+fn every_vertex_carries_the_layer_of_its_face() {
+    let sphere = layered(false);
+    let mesh = sphere.try_mesh().unwrap();
+    let faces = faces(&mesh);
+    assert_eq!(faces.len(), mesh.count_vertices());
+
+    for face in CubeFace::ALL {
+        for vertex in face.vertex_range(sphere.resolution) {
+            assert_eq!(faces[vertex], face.index() as u32);
+        }
+    }
+}
+
+#[test]
+fn layer_uvs_map_back_to_the_vertices_of_their_face() {
+    for inverted in [false, true] {
+        let sphere = layered(inverted);
+        let mesh = sphere.try_mesh().unwrap();
+        for ((pt, uv), layer) in points(&mesh).iter().zip(&uvs(&mesh)).zip(faces(&mesh)) {
+            let face = CubeFace::ALL[layer as usize];
+            let back = sphere.face_uv_to_point(face, uv).unwrap();
+            assert!(distance(&back, pt) < 1e-5, "{:?} -> {:?}", pt, back);
+        }
+    }
+}
+
+#[test]
+fn layer_uvs_without_their_face_map_to_nowhere() {
+    let sphere = layered(false);
+    for uv in [[0., 0.], [0.5, 0.5], [0.25, 1.]] {
+        assert_eq!(sphere.uv_to_point(&uv), None);
+        assert_eq!(sphere.uv_to_normal(&uv), None);
+        assert_eq!(sphere.uv_to_tangent(&uv), None);
+        assert!(sphere.face_uv_to_point(CubeFace::Top, &uv).is_some());
+    }
+    assert_eq!(sphere.face_uv_to_point(CubeFace::Top, &[1.5, 0.5]), None);
+}
@@ -0,0 +1,228 @@
+mod common;
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use bevy::render::mesh::MeshVertexAttribute;
+use bevy::render::render_resource::VertexFormat;
+use bevy_cube_sphere::{
+    AttributeHook, AttributeValue, CellId, CubeSphere, CubeSphereError, FaceEdge, LodQuadtree,
+    LodSeams, LodSettings, LodUpdate, VertexInfo, Viewpoint,
+};
+
+fn quadtree() -> LodQuadtree {
+    let settings = LodSettings {
+        max_level: 7,
+        patch_resolution: 9,
+        max_error: 0.02,
+        seams: LodSeams::Stitch,
+    };
+    LodQuadtree::new(CubeSphere::default(), settings).unwrap()
+}
+
+/// a viewpoint just above the surface, in a direction
+fn viewpoint(dir: [f32; 3], altitude: f32) -> Viewpoint {
+    let norm = (dir[0] * dir[0] + dir[1] * dir[1] + dir[2] * dir[2]).sqrt();
+    Viewpoint::new(dir.map(|c| c / norm * (1. + altitude)))
+}
+
+/// the selected patch containing a cell, `None` if the cell is split further
+fn covering(chunks: &BTreeSet<CellId>, cell: CellId) -> Option<CellId> {
+    let mut cell = Some(cell);
+    while let Some(c) = cell {
+        if chunks.contains(&c) {
+            return Some(c);
+        }
+        cell = c.parent();
+    }
+    None
+}
+
+#[test]
+fn selection_covers_the_sphere_once() {
+    let mut lod = quadtree();
+    lod.update(&viewpoint([1., 1., 0.3], 0.01));
+    let chunks: Vec<CellId> = lod.chunks().collect();
+
+    // every face is covered, by `4^level` patches per level
+    let area: f64 = chunks.iter().map(|c| 0.25_f64.powi(c.level() as i32)).sum();
+    assert!((area - 6.).abs() < 1e-9);
+    for (k, a) in chunks.iter().enumerate() {
+        assert!(chunks[k + 1..]
+            .iter()
+            .all(|b| !a.contains(b) && !b.contains(a)));
+    }
+    assert!(chunks.iter().any(|c| c.level() == 7));
+}
+
+#[test]
+fn selection_is_balanced_after_update() {
+    let mut lod = quadtree();
+    for (dir, altitude) in [
+        ([0., 0., 1.], 0.01),
+        ([1., 1., 1.], 0.001),
+        ([1., 1., 0.3], 0.05),
+        ([-0.2, -1., 0.7], 0.002),
+    ] {
+        lod.update(&viewpoint(dir, altitude));
+        let chunks: BTreeSet<CellId> = lod.chunks().collect();
+
+        for cell in &chunks {
+            for neighbor in cell.edge_neighbors() {
+                if let Some(coarse) = covering(&chunks, neighbor) {
+                    assert!(
+                        coarse.level() + 1 >= cell.level(),
+                        "{:?} borders {:?}",
+                        cell,
+                        coarse
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn update_reports_the_changes_of_the_selection() {
+    let mut lod = quadtree();
+
+    let first = lod.update(&viewpoint([0., 0., 1.], 0.01));
+    let before: BTreeSet<CellId> = lod.chunks().collect();
+    assert_eq!(first.added, before.iter().copied().collect::<Vec<_>>());
+    assert!(first.removed.is_empty() && first.restitched.is_empty());
+    let masks: Vec<(CellId, u8)> = before
+        .iter()
+        .map(|c| (*c, lod.stitch_mask(*c).unwrap()))
+        .collect();
+
+    let moved = lod.update(&viewpoint([0.3, 0.1, 1.], 0.01));
+    let after: BTreeSet<CellId> = lod.chunks().collect();
+    assert!(!moved.is_empty());
+    assert_eq!(
+        moved.added,
+        after.difference(&before).copied().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        moved.removed,
+        before.difference(&after).copied().collect::<Vec<_>>()
+    );
+    let restitched: Vec<CellId> = masks
+        .into_iter()
+        .filter(|(c, mask)| matches!(lod.stitch_mask(*c), Some(m) if m != *mask))
+        .map(|(c, _)| c)
+        .collect();
+    assert_eq!(moved.restitched, restitched);
+    assert!(!moved.restitched.is_empty());
+
+    // the same viewpoint again leaves the selection as it is
+    assert_eq!(
+        lod.update(&viewpoint([0.3, 0.1, 1.], 0.01)),
+        LodUpdate::default()
+    );
+    assert!(lod.update(&viewpoint([0.3, 0.1, 1.], 0.01)).is_empty());
+}
+
+#[test]
+fn stitch_masks_match_across_cube_edges() {
+    let mut lod = quadtree();
+    // near a cube corner, so that the levels change across the cube edges
+    lod.update(&viewpoint([1., 1., 0.9], 0.01));
+    let chunks: BTreeSet<CellId> = lod.chunks().collect();
+
+    let mut crossings = 0;
+    for cell in &chunks {
+        let mask = lod.stitch_mask(*cell).unwrap();
+        for (edge, neighbor) in FaceEdge::ALL.into_iter().zip(cell.edge_neighbors()) {
+            let coarse = match covering(&chunks, neighbor) {
+                Some(coarse) => coarse,
+                // split further, the finer patches stitch to this one
+                None => {
+                    assert_eq!(mask & 1 << edge.index(), 0);
+                    continue;
+                }
+            };
+            let stitched = mask & 1 << edge.index() != 0;
+            assert_eq!(stitched, coarse.level() < cell.level());
+
+            // the coarser patch does not stitch the same edge
+            if stitched && coarse.face() != cell.face() {
+                crossings += 1;
+                let parent = cell.parent().unwrap();
+                let back = coarse
+                    .edge_neighbors()
+                    .iter()
+                    .position(|n| *n == parent)
+                    .expect("the coarser patch borders the parent");
+                assert_eq!(lod.stitch_mask(coarse).unwrap() & 1 << back, 0);
+            }
+        }
+    }
+    assert!(crossings > 0);
+}
+
+/// writes a `Uint32` value into an attribute of some format
+struct Hook(VertexFormat);
+
+impl AttributeHook for Hook {
+    fn attributes(&self) -> Vec<MeshVertexAttribute> {
+        vec![MeshVertexAttribute::new("Vertex_Biome", 7_201, self.0)]
+    }
+
+    fn compute(&self, _: &VertexInfo, values: &mut [AttributeValue]) {
+        values[0] = AttributeValue::Uint32(0);
+    }
+}
+
+#[test]
+fn attribute_hooks_are_checked_by_the_quadtree() {
+    let with_hook = |format| CubeSphere {
+        attribute_hook: Some(Arc::new(Hook(format))),
+        ..Default::default()
+    };
+
+    // an attribute format the patches cannot hold is rejected up front
+    assert_eq!(
+        LodQuadtree::new(with_hook(VertexFormat::Uint16x4), LodSettings::default()).map(|_| ()),
+        Err(CubeSphereError::InvalidAttribute("Vertex_Biome"))
+    );
+
+    // a value of another format only shows when a patch is generated
+    let mut lod =
+        LodQuadtree::new(with_hook(VertexFormat::Float32), LodSettings::default()).unwrap();
+    lod.update(&viewpoint([1., 0., 0.], 1.));
+    let cell = lod.chunks().next().unwrap();
+    assert_eq!(
+        lod.chunk_mesh(cell).map(|_| ()),
+        Err(CubeSphereError::InvalidAttribute("Vertex_Biome"))
+    );
+
+    let lod = LodQuadtree::new(with_hook(VertexFormat::Uint32), LodSettings::default()).unwrap();
+    assert!(lod.chunk_mesh(cell).is_ok());
+}
+
+#[test]
+fn stitched_chunks_share_the_edges_of_their_coarser_neighbors() {
+    let mut lod = quadtree();
+    // near a cube corner, so that the levels change across the cube edges
+    lod.update(&viewpoint([1., 1., 0.9], 0.01));
+    let chunks: BTreeSet<CellId> = lod.chunks().collect();
+    let n = lod.settings().patch_resolution;
+
+    let mut crossings = 0;
+    for cell in &chunks {
+        let mask = lod.stitch_mask(*cell).unwrap();
+        for (edge, neighbor) in FaceEdge::ALL.into_iter().zip(cell.edge_neighbors()) {
+            if mask & 1 << edge.index() == 0 {
+                continue;
+            }
+            let coarse = covering(&chunks, neighbor).unwrap();
+            let fine_mesh = lod.chunk_mesh(*cell).unwrap();
+            let coarse_mesh = lod.chunk_mesh(coarse).unwrap();
+            common::assert_patch_edge_is_shared(&fine_mesh, n, edge, &coarse_mesh);
+            if coarse.face() != cell.face() {
+                crossings += 1;
+            }
+        }
+    }
+    assert!(crossings > 0);
+}
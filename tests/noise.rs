@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use bevy::prelude::Mesh;
+use bevy_cube_sphere::noise::{Fractal, NoiseBasis};
+use bevy_cube_sphere::{CubeSphere, NoiseSettings};
+
+/// directions spread over the whole sphere
+fn directions() -> Vec<[f32; 3]> {
+    let mut directions = Vec::new();
+    for lat in -8..=8 {
+        for lon in 0..32 {
+            let theta = lat as f32 / 16. * std::f32::consts::PI;
+            let phi = lon as f32 / 32. * std::f32::consts::TAU;
+            directions.push([
+                theta.cos() * phi.cos(),
+                theta.sin(),
+                theta.cos() * phi.sin(),
+            ]);
+        }
+    }
+    directions
+}
+
+/// every basis and fractal, with and without domain warping
+fn variants() -> Vec<NoiseSettings> {
+    let mut variants = Vec::new();
+    for basis in [NoiseBasis::Perlin, NoiseBasis::Simplex] {
+        for fractal in [Fractal::Fbm, Fractal::Ridged, Fractal::Billow] {
+            for warp in [0., 0.3] {
+                variants.push(NoiseSettings {
+                    seed: 1234,
+                    basis,
+                    fractal,
+                    warp,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    variants
+}
+
+fn heights(settings: &NoiseSettings) -> Vec<u32> {
+    directions()
+        .iter()
+        .map(|dir| settings.sample(dir).to_bits())
+        .collect()
+}
+
+#[test]
+fn the_same_seed_gives_the_same_heights() {
+    for settings in variants() {
+        let copy = settings;
+        assert_eq!(heights(&settings), heights(&copy));
+        assert!(heights(&settings).iter().any(|h| f32::from_bits(*h) != 0.));
+    }
+}
+
+#[test]
+fn different_seeds_give_different_heights() {
+    for settings in variants() {
+        let reseeded = NoiseSettings {
+            seed: settings.seed + 1,
+            ..settings
+        };
+        let (a, b) = (heights(&settings), heights(&reseeded));
+        let differing = a.iter().zip(&b).filter(|(a, b)| a != b).count();
+        assert!(differing > a.len() / 2, "{:?}", settings);
+    }
+}
+
+#[test]
+fn heights_stay_within_the_amplitude() {
+    for settings in variants() {
+        for dir in directions() {
+            let height = settings.sample(&dir);
+            assert!(height.is_finite() && height.abs() <= settings.amplitude);
+        }
+    }
+}
+
+#[test]
+fn the_same_seed_gives_the_same_terrain_mesh() {
+    let mesh = |seed: u32| -> Vec<[u32; 3]> {
+        let sphere = CubeSphere {
+            resolution: 12,
+            elevation: Some(Arc::new(NoiseSettings {
+                seed,
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let mesh = sphere.try_mesh().unwrap();
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(|values| values.as_float3())
+            .unwrap()
+            .iter()
+            .map(|p| p.map(f32::to_bits))
+            .collect()
+    };
+
+    assert_eq!(mesh(7), mesh(7));
+    assert_ne!(mesh(7), mesh(8));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn settings_survive_a_serde_round_trip() {
+    for settings in variants() {
+        let text = ron::to_string(&settings).unwrap();
+        let back: NoiseSettings = ron::from_str(&text).unwrap();
+        assert_eq!(back, settings);
+        assert_eq!(heights(&back), heights(&settings));
+    }
+
+    // the missing fields take their default
+    let partial: NoiseSettings = ron::from_str("(seed: 42, basis: Perlin)").unwrap();
+    assert_eq!(
+        partial,
+        NoiseSettings {
+            seed: 42,
+            basis: NoiseBasis::Perlin,
+            ..Default::default()
+        }
+    );
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bevy::prelude::Mesh;
+use bevy::render::mesh::Indices;
+use bevy::render::render_resource::PrimitiveTopology;
+use bevy_cube_sphere::uv::{Equirectangular, FaceTiles};
+use bevy_cube_sphere::{
+    CellId, CubeFace, CubeSphere, CubeSphereError, GridLines, NoiseSettings, PatchSeam, SphereClip,
+};
+use common::{dot, normals, points};
+
+/// a bumpy sphere, so that the normals of the faces around a vertex differ
+fn terrain(welded: bool) -> CubeSphere {
+    CubeSphere {
+        resolution: 16,
+        welded,
+        elevation: Some(Arc::new(NoiseSettings {
+            amplitude: 0.1,
+            ..Default::default()
+        })),
+        uv_layout: Arc::new(FaceTiles::cross(0.)),
+        ..Default::default()
+    }
+}
+
+/// check that the vertices at the same position have the same normal
+/// ## Return
+/// - the number of positions with several copies
+fn assert_copies_agree(mesh: &Mesh) -> usize {
+    let mut shared: HashMap<[u32; 3], ([u32; 3], usize)> = HashMap::new();
+    for (point, normal) in points(mesh).iter().zip(&normals(mesh)) {
+        let (first, copies) = shared
+            .entry(point.map(f32::to_bits))
+            .or_insert((normal.map(f32::to_bits), 0));
+        assert_eq!(*first, normal.map(f32::to_bits), "{:?}", point);
+        *copies += 1;
+    }
+    shared.values().filter(|(_, copies)| *copies > 1).count()
+}
+
+#[test]
+fn copies_of_a_vertex_get_the_same_normal() {
+    let sphere = terrain(false);
+    let mut mesh = sphere.try_mesh().unwrap();
+    sphere.recompute_normals(&mut mesh).unwrap();
+
+    // the borders of the faces are copied
+    assert!(assert_copies_agree(&mesh) > 0);
+}
+
+#[test]
+fn welded_and_split_meshes_get_the_same_normals() {
+    let split = terrain(false);
+    let welded = terrain(true);
+    let mut split_mesh = split.try_mesh().unwrap();
+    let mut welded_mesh = welded.try_mesh().unwrap();
+    split.recompute_normals(&mut split_mesh).unwrap();
+    welded.recompute_normals(&mut welded_mesh).unwrap();
+
+    let welded_normals: HashMap<[u32; 3], [f32; 3]> = points(&welded_mesh)
+        .iter()
+        .zip(normals(&welded_mesh))
+        .map(|(point, normal)| (point.map(f32::to_bits), normal))
+        .collect();
+    for (point, normal) in points(&split_mesh).iter().zip(&normals(&split_mesh)) {
+        let welded = welded_normals[&point.map(f32::to_bits)];
+        assert!(dot(normal, &welded) > 1. - 1e-5, "{:?}", point);
+    }
+}
+
+#[test]
+fn recomputed_normals_follow_the_surface() {
+    let sphere = CubeSphere {
+        resolution: 32,
+        ..Default::default()
+    };
+    let generated = sphere.try_mesh().unwrap();
+    let mut mesh = generated.clone();
+    sphere.recompute_normals(&mut mesh).unwrap();
+
+    let before = normals(&generated);
+    let after = normals(&mesh);
+    for (a, b) in before.iter().zip(&after) {
+        assert!(dot(a, b) > 0.999);
+        assert!((dot(b, b) - 1.).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn cut_split_and_patch_meshes_get_their_normals_recomputed() {
+    let sphere = CubeSphere {
+        resolution: 32,
+        ..Default::default()
+    };
+    let clipped = CubeSphere {
+        clip: Some(SphereClip::hemisphere([1., 0.7, 0.2])),
+        ..sphere.clone()
+    };
+    let equirect = CubeSphere {
+        uv_layout: Arc::new(Equirectangular),
+        ..sphere.clone()
+    };
+
+    let mut meshes = vec![
+        clipped.try_mesh().unwrap(),
+        equirect.try_mesh().unwrap(),
+        sphere
+            .try_patch_mesh(
+                CellId::from_face_ij(CubeFace::Top, 1, 1, 0),
+                9,
+                PatchSeam::None,
+            )
+            .unwrap(),
+    ];
+    meshes.extend(sphere.try_meshes(1000).unwrap());
+
+    for mut mesh in meshes {
+        let generated = normals(&mesh);
+        sphere.recompute_normals(&mut mesh).unwrap();
+        assert_copies_agree(&mesh);
+
+        // the vertices along a cut only see the triangles on one side
+        for (a, b) in generated.iter().zip(&normals(&mesh)) {
+            assert!(dot(a, b) > 0.99, "{:?} {:?}", a, b);
+            assert!((dot(b, b) - 1.).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn meshes_without_triangles_or_positions_are_rejected() {
+    let sphere = CubeSphere {
+        resolution: 9,
+        ..Default::default()
+    };
+
+    // no triangles
+    let mut mesh = sphere.try_mesh().unwrap();
+    mesh.set_indices(None);
+    assert_eq!(
+        sphere.recompute_normals(&mut mesh),
+        Err(CubeSphereError::IncompatibleMesh(6 * 9 * 9))
+    );
+
+    // lines
+    let mut mesh = sphere.try_wireframe_mesh(GridLines::All).unwrap();
+    assert_eq!(
+        sphere.recompute_normals(&mut mesh),
+        Err(CubeSphereError::IncompatibleMesh(6 * 9 * 9))
+    );
+
+    // no positions
+    let mut mesh = sphere.try_mesh().unwrap();
+    let points = mesh.remove_attribute(Mesh::ATTRIBUTE_POSITION).unwrap();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vec![[0_f32; 4]; points.len()]);
+    assert_eq!(
+        sphere.recompute_normals(&mut mesh),
+        Err(CubeSphereError::IncompatibleMesh(6 * 9 * 9))
+    );
+
+    // an index past the vertices
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0_f32; 3]; 3]);
+    mesh.set_indices(Some(Indices::U16(vec![0, 1, 3])));
+    assert_eq!(
+        sphere.recompute_normals(&mut mesh),
+        Err(CubeSphereError::IncompatibleMesh(3))
+    );
+}
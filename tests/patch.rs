@@ -0,0 +1,89 @@
+mod common;
+
+use bevy::prelude::Mesh;
+use bevy_cube_sphere::{CellId, CubeFace, CubeSphere, FaceEdge, PatchSeam};
+use common::{assert_patch_edge_is_shared, boundary_edges, indices, patch_edge_vertices, points};
+
+const N: usize = 9;
+
+/// a patch of the front face
+fn patch(level: u8, i: u32, j: u32, seam: PatchSeam) -> Mesh {
+    CubeSphere::default()
+        .try_patch_mesh(CellId::from_face_ij(CubeFace::Front, level, i, j), N, seam)
+        .unwrap()
+}
+
+#[test]
+fn stitched_patches_share_the_edges_of_a_coarser_neighbor() {
+    // the finer patches of the cell `(1, 1)` along the border with `(0, 0)`
+    let coarse = patch(1, 0, 0, PatchSeam::None);
+    let stitch = PatchSeam::Stitch(1 << FaceEdge::MinU.index());
+    for j in [0, 1] {
+        let fine = patch(2, 2, j, stitch);
+        assert_patch_edge_is_shared(&fine, N, FaceEdge::MinU, &coarse);
+    }
+
+    // without the stitch, every other vertex of the finer edge is a T-junction
+    let fine = patch(2, 2, 0, PatchSeam::None);
+    let fine_points = points(&fine);
+    let coarse_edges = boundary_edges(&coarse);
+    let along: Vec<[u32; 3]> = patch_edge_vertices(N, FaceEdge::MinU)
+        .into_iter()
+        .map(|v| fine_points[v].map(f32::to_bits))
+        .collect();
+    let cracked = boundary_edges(&fine)
+        .into_iter()
+        .filter(|segment| segment.iter().all(|end| along.contains(end)))
+        .filter(|segment| !coarse_edges.contains(segment))
+        .count();
+    assert_eq!(cracked, N - 1);
+}
+
+#[test]
+fn stitched_patches_keep_the_other_edges() {
+    let plain = patch(2, 1, 2, PatchSeam::None);
+    for edge in FaceEdge::ALL {
+        let stitched = patch(2, 1, 2, PatchSeam::Stitch(1 << edge.index()));
+        assert_eq!(stitched.count_vertices(), N * N);
+
+        // two triangles per pair of edge segments fold into one
+        assert_eq!(
+            indices(&stitched).len() / 3,
+            indices(&plain).len() / 3 - (N - 1) / 2
+        );
+        for other in FaceEdge::ALL.into_iter().filter(|e| *e != edge) {
+            assert_patch_edge_is_shared(&stitched, N, other, &plain);
+        }
+    }
+}
+
+#[test]
+fn skirts_hang_below_every_border() {
+    let depth = 0.05;
+    let plain = patch(1, 1, 0, PatchSeam::None);
+    let skirted = patch(1, 1, 0, PatchSeam::Skirt(depth));
+
+    // a vertex and two triangles below every segment of the border
+    let rim = 4 * (N - 1);
+    assert_eq!(skirted.count_vertices(), N * N + rim);
+    assert_eq!(
+        indices(&skirted).len() / 3,
+        indices(&plain).len() / 3 + 2 * rim
+    );
+
+    // the border of the patch is now the bottom of the skirts
+    let points = points(&skirted);
+    for bottom in &points[N * N..] {
+        let length = bottom.iter().map(|c| c * c).sum::<f32>().sqrt();
+        assert!((length - (1. - depth)).abs() < 1e-6, "{:?}", bottom);
+    }
+    let bottom: Vec<[u32; 3]> = points[N * N..]
+        .iter()
+        .map(|pt| pt.map(f32::to_bits))
+        .collect();
+    let rim_edges = boundary_edges(&skirted);
+    assert_eq!(rim_edges.len(), rim);
+    for segment in rim_edges {
+        assert!(segment.iter().all(|end| bottom.contains(end)));
+    }
+}
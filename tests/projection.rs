@@ -0,0 +1,115 @@
+mod common;
+
+use bevy_cube_sphere::projection::{Everitt, Normalized, Quadrilateralized, Spherified, Tangent};
+use bevy_cube_sphere::{CubeFace, CubeProjection};
+use common::distance;
+
+fn projections() -> [&'static dyn CubeProjection; 5] {
+    [
+        &Normalized,
+        &Spherified,
+        &Tangent,
+        &Everitt,
+        &Quadrilateralized,
+    ]
+}
+
+/// points of every face on a grid, the edges and corners of the cube included
+fn cube_points() -> Vec<[f32; 3]> {
+    const STEPS: i32 = 16;
+    let mut points = Vec::new();
+    for face in CubeFace::ALL {
+        let normal = face.normal();
+        let [u, v] = face.axes();
+        for i in 0..=STEPS {
+            for j in 0..=STEPS {
+                let a = 2. * i as f32 / STEPS as f32 - 1.;
+                let b = 2. * j as f32 / STEPS as f32 - 1.;
+                points.push([0, 1, 2].map(|k| normal[k] + a * u[k] + b * v[k]));
+            }
+        }
+    }
+    points
+}
+
+#[test]
+fn projections_are_unit_length() {
+    for projection in projections() {
+        for cube in cube_points() {
+            let sphere = projection.cube_to_sphere(&cube);
+            let length = distance(&sphere, &[0.; 3]);
+            assert!(
+                (length - 1.).abs() < 1e-5,
+                "{:?} {:?} {}",
+                projection,
+                cube,
+                length
+            );
+        }
+    }
+}
+
+#[test]
+fn projections_round_trip_through_the_cube() {
+    for projection in projections() {
+        for cube in cube_points() {
+            let sphere = projection.cube_to_sphere(&cube);
+            let back = projection.sphere_to_cube(&sphere);
+            // a point on an edge may come back on either face, at the same place
+            assert!(
+                distance(&cube, &back) < 1e-4,
+                "{:?} {:?} {:?}",
+                projection,
+                cube,
+                back
+            );
+            assert!(back.iter().all(|c| c.abs() <= 1.));
+        }
+    }
+}
+
+#[test]
+fn projections_round_trip_through_the_sphere() {
+    for projection in projections() {
+        for lat in -9..=9 {
+            for lon in 0..36 {
+                let theta = lat as f32 * 10_f32.to_radians();
+                let phi = lon as f32 * 10_f32.to_radians();
+                let sphere = [
+                    theta.cos() * phi.cos(),
+                    theta.sin(),
+                    theta.cos() * phi.sin(),
+                ];
+                let back = projection.cube_to_sphere(&projection.sphere_to_cube(&sphere));
+                assert!(
+                    distance(&sphere, &back) < 1e-4,
+                    "{:?} {:?} {:?}",
+                    projection,
+                    sphere,
+                    back
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn projections_keep_the_edges_and_corners_of_the_cube() {
+    for projection in projections() {
+        // the corners land on the diagonals
+        let corner = projection.cube_to_sphere(&[1., -1., 1.]);
+        let diagonal = 1. / 3_f32.sqrt();
+        assert!(distance(&corner, &[diagonal, -diagonal, diagonal]) < 1e-5);
+
+        // the middle of an edge lands between its two faces
+        let edge = projection.cube_to_sphere(&[0., 1., -1.]);
+        let half = 1. / 2_f32.sqrt();
+        assert!(distance(&edge, &[0., half, -half]) < 1e-5);
+
+        // the center of a face is its normal
+        for face in CubeFace::ALL {
+            let center = projection.cube_to_sphere(&face.normal());
+            assert!(distance(&center, &face.normal()) < 1e-6);
+        }
+    }
+}
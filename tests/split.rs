@@ -0,0 +1,86 @@
+mod common;
+
+use std::sync::Arc;
+
+use bevy::render::mesh::Indices;
+use bevy_cube_sphere::uv::Equirectangular;
+use bevy_cube_sphere::{CellId, CubeFace, CubeSphere, PatchSeam, SphereClip};
+use common::triangle_points;
+
+fn sorted_triangles(meshes: &[bevy::prelude::Mesh]) -> Vec<[[u32; 3]; 3]> {
+    let mut triangles: Vec<_> = meshes.iter().flat_map(triangle_points).collect();
+    triangles.sort();
+    triangles
+}
+
+#[test]
+fn index_width_follows_the_vertex_count() {
+    let sphere = CubeSphere::default();
+    let cell = CellId::from_face(CubeFace::Top);
+
+    // `256²` vertices are the most 16 bit indices address
+    let mesh = sphere.try_patch_mesh(cell, 256, PatchSeam::None).unwrap();
+    assert_eq!(mesh.count_vertices(), 65_536);
+    assert!(matches!(mesh.indices(), Some(Indices::U16(_))));
+    assert_eq!(mesh.indices().unwrap().iter().max(), Some(65_535));
+
+    let mesh = sphere.try_patch_mesh(cell, 257, PatchSeam::None).unwrap();
+    assert_eq!(mesh.count_vertices(), 66_049);
+    assert!(matches!(mesh.indices(), Some(Indices::U32(_))));
+
+    // `6 * 104²` and `6 * 105²` vertices
+    for (resolution, narrow) in [(104, true), (105, false)] {
+        let mesh = CubeSphere {
+            resolution,
+            ..Default::default()
+        }
+        .try_mesh()
+        .unwrap();
+        assert_eq!(matches!(mesh.indices(), Some(Indices::U16(_))), narrow);
+    }
+}
+
+#[test]
+fn split_meshes_keep_every_triangle_within_the_budget() {
+    let spheres = [
+        CubeSphere {
+            resolution: 33,
+            ..Default::default()
+        },
+        CubeSphere {
+            resolution: 33,
+            clip: Some(SphereClip::hemisphere([1., 0.7, 0.2])),
+            ..Default::default()
+        },
+        CubeSphere {
+            resolution: 33,
+            uv_layout: Arc::new(Equirectangular),
+            ..Default::default()
+        },
+    ];
+    for sphere in spheres {
+        let whole = sorted_triangles(&[sphere.try_mesh().unwrap()]);
+        for max_vertices in [3, 4, 100, 1000, 65_536] {
+            let meshes = sphere.try_meshes(max_vertices).unwrap();
+            for mesh in &meshes {
+                assert!(mesh.count_vertices() <= max_vertices);
+                assert!(matches!(mesh.indices(), Some(Indices::U16(_))));
+            }
+            assert_eq!(sorted_triangles(&meshes), whole, "{}", max_vertices);
+        }
+    }
+}
+
+#[test]
+fn split_meshes_cover_a_block_of_a_single_face() {
+    let sphere = CubeSphere {
+        resolution: 64,
+        ..Default::default()
+    };
+    // `2 × 3` blocks per face of at most `33 × 22` vertices, spread evenly
+    let meshes = sphere.try_meshes(1000).unwrap();
+    assert_eq!(meshes.len(), 6 * 2 * 3);
+    for mesh in &meshes {
+        assert!([33 * 22, 32 * 22].contains(&mesh.count_vertices()));
+    }
+}
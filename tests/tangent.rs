@@ -0,0 +1,91 @@
+mod common;
+
+use bevy_cube_sphere::{CubeFace, CubeSphere};
+use common::{cross, dot, indices, normalize, normals, points, tangents, uvs};
+
+/// the derivatives of the position along `u` and `v` over a triangle, from
+/// its positions and uv coordinates
+fn uv_derivatives(points: [[f32; 3]; 3], uvs: [[f32; 2]; 3]) -> ([f32; 3], [f32; 3]) {
+    let e1 = [0, 1, 2].map(|k| points[1][k] - points[0][k]);
+    let e2 = [0, 1, 2].map(|k| points[2][k] - points[0][k]);
+    let d1 = [uvs[1][0] - uvs[0][0], uvs[1][1] - uvs[0][1]];
+    let d2 = [uvs[2][0] - uvs[0][0], uvs[2][1] - uvs[0][1]];
+    let det = d1[0] * d2[1] - d2[0] * d1[1];
+
+    let dp_du = [0, 1, 2].map(|k| (e1[k] * d2[1] - e2[k] * d1[1]) / det);
+    let dp_dv = [0, 1, 2].map(|k| (e2[k] * d1[0] - e1[k] * d2[0]) / det);
+    (dp_du, dp_dv)
+}
+
+#[test]
+fn tangents_follow_the_uv_derivative_on_every_face() {
+    let sphere = CubeSphere {
+        resolution: 24,
+        ..Default::default()
+    };
+    let mesh = sphere.try_mesh().unwrap();
+
+    let (points, normals) = (points(&mesh), normals(&mesh));
+    let (tangents, uvs) = (tangents(&mesh), uvs(&mesh));
+    let indices = indices(&mesh);
+
+    for face in CubeFace::ALL {
+        let range = face.vertex_range(sphere.resolution);
+        let mut checked = 0;
+
+        for triangle in indices.chunks(3).filter(|t| range.contains(&t[0])) {
+            let (dp_du, dp_dv) = uv_derivatives(
+                [0, 1, 2].map(|k| points[triangle[k]]),
+                [0, 1, 2].map(|k| uvs[triangle[k]]),
+            );
+
+            for v in triangle {
+                let normal = &normals[*v];
+                let tangent = [tangents[*v][0], tangents[*v][1], tangents[*v][2]];
+
+                // the reference, made perpendicular to the vertex normal
+                let along = dot(&dp_du, normal);
+                let reference = normalize(&[0, 1, 2].map(|k| dp_du[k] - along * normal[k]));
+                assert!(
+                    dot(&tangent, &reference) > 0.99,
+                    "{:?}: tangent {:?} does not follow the uv derivative {:?}",
+                    face,
+                    tangent,
+                    reference
+                );
+
+                let handedness = dot(&cross(normal, &tangent), &dp_dv).signum();
+                assert_eq!(
+                    tangents[*v][3], handedness,
+                    "{:?}: wrong bitangent sign",
+                    face
+                );
+
+                assert!((dot(&tangent, &tangent) - 1.).abs() < 1e-5);
+                assert!(dot(&tangent, normal).abs() < 1e-5);
+            }
+            checked += 1;
+        }
+
+        assert_eq!(checked, 2 * (sphere.resolution - 1).pow(2));
+    }
+}
+
+#[test]
+fn every_face_has_the_same_handedness() {
+    let sphere = CubeSphere::default();
+    let mesh = sphere.try_mesh().unwrap();
+    let tangents = tangents(&mesh);
+
+    // the flipped faces undo the mirroring of looking at the opposite side of
+    // the cube, so every disc of the texture reads the same way from outside,
+    // with `v` growing downwards
+    for face in CubeFace::ALL {
+        assert!(
+            face.vertex_range(sphere.resolution)
+                .all(|v| tangents[v][3] == -1.),
+            "{:?}: unexpected handedness",
+            face
+        );
+    }
+}
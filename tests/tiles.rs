@@ -0,0 +1,80 @@
+mod common;
+
+use std::sync::Arc;
+
+use bevy_cube_sphere::uv::FaceTiles;
+use bevy_cube_sphere::{CellId, CubeFace, CubeSphere, CubeSphereError, PatchSeam};
+use common::{distance, sphere_points};
+
+/// every arrangement, with a gutter so that every border of the texture
+/// shows a single face
+fn layouts() -> Vec<FaceTiles> {
+    let mut layouts = Vec::new();
+    for gutter in [0.01, 0.2] {
+        layouts.extend([
+            FaceTiles::atlas(gutter),
+            FaceTiles::cross(gutter),
+            FaceTiles::strip(gutter),
+        ]);
+    }
+    layouts
+}
+
+fn sphere(layout: FaceTiles) -> CubeSphere {
+    CubeSphere {
+        resolution: 9,
+        uv_layout: Arc::new(layout),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn every_point_round_trips_through_each_of_its_tiles() {
+    for layout in layouts() {
+        let sphere = sphere(layout);
+        for pt in sphere_points(36) {
+            let uvs = sphere.point_to_uvs(&pt);
+            assert!(!uvs.is_empty());
+            for uv in &uvs {
+                assert!((0. ..=1.).contains(&uv[0]) && (0. ..=1.).contains(&uv[1]));
+                let back = sphere
+                    .uv_to_point(uv)
+                    .unwrap_or_else(|| panic!("{:?} of {:?} maps to nowhere", uv, pt));
+                assert!(
+                    distance(&back, &pt) < 1e-5,
+                    "{:?}: {:?} -> {:?} -> {:?}",
+                    layout,
+                    pt,
+                    uv,
+                    back
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn gutters_out_of_range_are_rejected() {
+    for gutter in [-0.1, 0.5, 0.7, f32::NAN, f32::INFINITY] {
+        for layout in [
+            FaceTiles::atlas(gutter),
+            FaceTiles::cross(gutter),
+            FaceTiles::strip(gutter),
+        ] {
+            let sphere = sphere(layout);
+            assert!(matches!(
+                sphere.try_mesh(),
+                Err(CubeSphereError::InvalidGutter(_))
+            ));
+            let cell = CellId::from_face(CubeFace::Front);
+            assert!(matches!(
+                sphere.try_patch_mesh(cell, 9, PatchSeam::None),
+                Err(CubeSphereError::InvalidGutter(_))
+            ));
+        }
+    }
+
+    for gutter in [0., 0.49] {
+        assert!(sphere(FaceTiles::cross(gutter)).try_mesh().is_ok());
+    }
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use std::sync::Arc;
+
+use bevy_cube_sphere::uv::{DiscPacking, Equirectangular, FaceTiles};
+use bevy_cube_sphere::{CubeSphere, UvLayout};
+use common::{distance, dot, points, sphere_points, tangents, uvs};
+
+/// the number of texels along each side of the texture swept by the tests
+const TEXELS: u32 = 1024;
+
+fn uv_distance(a: &[f32; 2], b: &[f32; 2]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
+
+/// every layout whose uv coordinates map back to a single point, with a
+/// gutter for the tiles so that every border of the texture shows a single
+/// face, and the largest distance between a vertex and its uv mapped back
+fn layouts() -> Vec<(Arc<dyn UvLayout>, f32)> {
+    let mut layouts: Vec<(Arc<dyn UvLayout>, f32)> = vec![(Arc::new(DiscPacking), 1e-5)];
+    for gutter in [0.01, 0.2] {
+        layouts.extend([
+            (
+                Arc::new(FaceTiles::atlas(gutter)) as Arc<dyn UvLayout>,
+                1e-5,
+            ),
+            (Arc::new(FaceTiles::cross(gutter)), 1e-5),
+            (Arc::new(FaceTiles::strip(gutter)), 1e-5),
+        ]);
+    }
+    // the longitude of the points near the poles is sensitive to rounding
+    layouts.push((Arc::new(Equirectangular), 1e-4));
+    layouts
+}
+
+#[test]
+fn every_point_round_trips_through_each_of_its_uvs() {
+    let sphere = CubeSphere::default();
+    for pt in sphere_points(90) {
+        let uvs = sphere.point_to_uvs(&pt);
+        for (axis, uv) in uvs.iter().enumerate() {
+            let back = sphere
+                .uv_to_point(uv)
+                .unwrap_or_else(|| panic!("{:?} of {:?} maps to nowhere", uv, pt));
+
+            // the coordinate dropped by a disc is recovered from its square,
+            // precise away from the rim of the disc only
+            let tolerance = if pt[axis].abs() > 0.05 { 1e-5 } else { 2e-3 };
+            assert!(
+                distance(&back, &pt) < tolerance,
+                "{:?} -> {:?} -> {:?}",
+                pt,
+                uv,
+                back
+            );
+        }
+    }
+}
+
+#[test]
+fn every_texel_on_a_disc_round_trips() {
+    let sphere = CubeSphere::default();
+    let mut on_sphere = 0;
+    for ix in 0..TEXELS {
+        for iy in 0..TEXELS {
+            let uv = [
+                (ix as f32 + 0.5) / TEXELS as f32,
+                (iy as f32 + 0.5) / TEXELS as f32,
+            ];
+            let pt = match sphere.uv_to_point(&uv) {
+                Some(pt) => pt,
+                None => continue,
+            };
+            on_sphere += 1;
+
+            assert!((dot(&pt, &pt).sqrt() - 1.).abs() < 1e-6, "{:?}", pt);
+            let closest = sphere
+                .point_to_uvs(&pt)
+                .iter()
+                .map(|other| uv_distance(other, &uv))
+                .fold(f32::MAX, f32::min);
+            assert!(closest < 1e-5, "{:?} -> {:?}", uv, pt);
+
+            let normal = sphere.uv_to_normal(&uv).unwrap();
+            assert!(distance(&normal, &pt) < 1e-6);
+        }
+    }
+
+    // six discs of radius `0.1876806`
+    let expected = 6. * std::f32::consts::PI * 0.1876806_f32.powi(2) * (TEXELS * TEXELS) as f32;
+    assert!(
+        (on_sphere as f32 / expected - 1.).abs() < 0.01,
+        "{}",
+        on_sphere
+    );
+}
+
+#[test]
+fn uvs_of_the_mesh_map_back_to_its_vertices() {
+    for (uv_layout, tolerance) in layouts() {
+        // an odd resolution puts a vertex on the poles and the antimeridian
+        for resolution in [8, 9, 16] {
+            let sphere = CubeSphere {
+                resolution,
+                uv_layout: uv_layout.clone(),
+                ..Default::default()
+            };
+            common::assert_uvs_map_back(&sphere, tolerance);
+        }
+    }
+}
+
+#[test]
+fn tangents_of_the_mesh_map_back_to_its_vertices() {
+    let sphere = CubeSphere {
+        resolution: 16,
+        ..Default::default()
+    };
+    let mesh = sphere.try_mesh().unwrap();
+
+    for ((pt, tangent), uv) in points(&mesh).iter().zip(&tangents(&mesh)).zip(&uvs(&mesh)) {
+        let back = sphere.uv_to_point(uv).unwrap();
+        assert!(distance(&back, pt) < 1e-5, "{:?} -> {:?}", pt, back);
+
+        let back_tangent = sphere.uv_to_tangent(uv).unwrap();
+        let along = dot(
+            &[back_tangent[0], back_tangent[1], back_tangent[2]],
+            &[tangent[0], tangent[1], tangent[2]],
+        );
+        assert!(along > 0.9999, "{:?} -> {:?}", tangent, back_tangent);
+        assert_eq!(back_tangent[3], tangent[3]);
+    }
+}
+
+#[test]
+fn uvs_off_the_discs_map_to_nowhere() {
+    let sphere = CubeSphere::default();
+    for uv in [[1., 1.], [0., 0.], [1., 0.], [0.99, 0.5]] {
+        assert_eq!(sphere.uv_to_point(&uv), None, "{:?}", uv);
+    }
+}
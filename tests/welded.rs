@@ -0,0 +1,103 @@
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bevy::prelude::Mesh;
+use bevy_cube_sphere::uv::{DiscPacking, Equirectangular, FaceTiles};
+use bevy_cube_sphere::{CubeSphere, UvLayout};
+use common::{indices, normals, points};
+
+/// the number of distinct vertices of the surface of the cube
+fn shared_count(n: usize) -> usize {
+    6 * n * n - 12 * n + 8
+}
+
+fn layouts() -> Vec<Arc<dyn UvLayout>> {
+    vec![
+        Arc::new(DiscPacking),
+        Arc::new(FaceTiles::cross(0.)),
+        Arc::new(Equirectangular),
+    ]
+}
+
+#[test]
+fn welded_meshes_share_every_border_vertex() {
+    for uv_layout in layouts() {
+        for n in [2, 8, 9] {
+            let mesh = CubeSphere {
+                resolution: n,
+                welded: true,
+                uv_layout: uv_layout.clone(),
+                ..Default::default()
+            }
+            .try_mesh()
+            .unwrap();
+
+            assert_eq!(mesh.count_vertices(), shared_count(n), "{:?}", uv_layout);
+            assert!(mesh.attribute(Mesh::ATTRIBUTE_UV_0).is_none());
+            assert!(mesh.attribute(Mesh::ATTRIBUTE_TANGENT).is_none());
+        }
+    }
+}
+
+#[test]
+fn welded_vertices_keep_the_position_and_normal_of_every_copy() {
+    let sphere = CubeSphere {
+        resolution: 8,
+        ..Default::default()
+    };
+    let split = sphere.try_mesh().unwrap();
+    let welded = CubeSphere {
+        welded: true,
+        ..sphere.clone()
+    }
+    .try_mesh()
+    .unwrap();
+
+    // every triangle keeps its positions and normals
+    let (split_points, split_normals) = (points(&split), normals(&split));
+    let (welded_points, welded_normals) = (points(&welded), normals(&welded));
+    let split_indices = indices(&split);
+    let welded_indices = indices(&welded);
+    assert_eq!(split_indices.len(), welded_indices.len());
+    for (s, w) in split_indices.into_iter().zip(welded_indices) {
+        assert_eq!(split_points[s], welded_points[w]);
+        assert_eq!(split_normals[s], welded_normals[w]);
+    }
+
+    // so does the remap of the per-face vertices
+    let remap = sphere.weld_map().unwrap();
+    assert_eq!(remap.len(), 6 * 8 * 8);
+    for (s, w) in remap.iter().enumerate() {
+        assert_eq!(split_points[s], welded_points[*w as usize]);
+    }
+    assert_eq!(
+        remap.iter().max().map(|w| *w as usize + 1),
+        Some(shared_count(8))
+    );
+}
+
+#[test]
+fn welded_meshes_are_watertight() {
+    let mesh = CubeSphere {
+        resolution: 9,
+        welded: true,
+        ..Default::default()
+    }
+    .try_mesh()
+    .unwrap();
+
+    // every edge borders two triangles, in opposite directions
+    let mut edges = HashMap::new();
+    for triangle in indices(&mesh).chunks(3) {
+        for k in 0..3 {
+            let edge = (triangle[k], triangle[(k + 1) % 3]);
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+    for (&(a, b), count) in &edges {
+        assert_eq!(*count, 1);
+        assert_eq!(edges.get(&(b, a)), Some(&1), "{} {}", a, b);
+    }
+}
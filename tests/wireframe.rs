@@ -0,0 +1,97 @@
+mod common;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use bevy::prelude::Mesh;
+use bevy_cube_sphere::uv::Equirectangular;
+use bevy_cube_sphere::{CubeSphere, GridLines};
+
+fn max_index(mesh: &Mesh) -> usize {
+    mesh.indices().unwrap().iter().max().unwrap()
+}
+
+#[test]
+fn wireframe_indices_stay_in_range_with_the_equirectangular_layout() {
+    // an odd resolution puts a grid vertex on each pole, and cuts the grid
+    // along the antimeridian
+    for resolution in [3, 9, 17] {
+        for welded in [false, true] {
+            let sphere = CubeSphere {
+                resolution,
+                welded,
+                uv_layout: Arc::new(Equirectangular),
+                ..Default::default()
+            };
+            for lines in [GridLines::All, GridLines::Every(2), GridLines::FaceBorders] {
+                let mesh = sphere.try_wireframe_mesh(lines).unwrap();
+                assert!(max_index(&mesh) < mesh.count_vertices());
+            }
+        }
+    }
+}
+
+#[test]
+fn wireframe_lines_cover_the_whole_grid_with_the_equirectangular_layout() {
+    let sphere = CubeSphere {
+        resolution: 9,
+        uv_layout: Arc::new(Equirectangular),
+        ..Default::default()
+    };
+    let mesh = sphere.try_wireframe_mesh(GridLines::All).unwrap();
+
+    // every vertex of the face grids is drawn, 2 * 9 * 8 edges per face
+    assert_eq!(mesh.count_vertices(), 6 * 9 * 9);
+    assert_eq!(mesh.indices().unwrap().len(), 6 * 144 * 2);
+}
+
+/// the lines of a mesh as pairs of positions, in increasing order
+fn lines(mesh: &Mesh) -> Vec<[[u32; 3]; 2]> {
+    let points = common::points(mesh);
+    common::indices(mesh)
+        .chunks(2)
+        .map(|line| {
+            let mut ends = [line[0], line[1]].map(|v| points[v].map(f32::to_bits));
+            ends.sort();
+            ends
+        })
+        .collect()
+}
+
+#[test]
+fn wireframe_draws_every_edge_of_the_default_layout_once() {
+    let n = 9;
+    for welded in [false, true] {
+        let sphere = CubeSphere {
+            resolution: n,
+            welded,
+            ..Default::default()
+        };
+
+        // `2 * n * (n - 1)` edges per face, the `12` cube edges shared when welded
+        for (lines_drawn, per_face, shared) in [
+            (GridLines::All, 2 * n * (n - 1), 12 * (n - 1)),
+            (GridLines::FaceBorders, 4 * (n - 1), 12 * (n - 1)),
+        ] {
+            let mesh = sphere.try_wireframe_mesh(lines_drawn).unwrap();
+            let lines = lines(&mesh);
+            let expected = if welded {
+                6 * per_face - shared
+            } else {
+                6 * per_face
+            };
+            assert_eq!(lines.len(), expected, "{:?} {}", lines_drawn, welded);
+
+            let distinct: HashSet<_> = lines.iter().collect();
+            if welded {
+                assert_eq!(distinct.len(), lines.len());
+            } else {
+                // the border edges are drawn once per face
+                assert_eq!(distinct.len(), lines.len() - shared);
+            }
+            for [a, b] in &lines {
+                assert_ne!(a, b);
+            }
+        }
+    }
+}